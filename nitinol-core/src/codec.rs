@@ -0,0 +1,111 @@
+//! Serialization formats usable by [`Event`](crate::event::Event) implementations,
+//! each behind the feature of the same name.
+//!
+//! Every format is a module with an `encode` and a `decode` function, which is what
+//! `#[persist(codec = "json")]` on the `Event` derive expands to.
+//! [`default_codec!`](crate::default_codec) picks the format of the events of a crate
+//! that name neither a `codec` nor `enc`/`dec`.
+
+/// Set the format of the events of this crate that do not name one.
+///
+/// Must be invoked at the root of the crate.
+///
+/// ```ignore
+/// nitinol::default_codec!(json);
+///
+/// #[derive(Event, Serialize, Deserialize)]
+/// pub struct Registered { email: String }
+/// ```
+#[macro_export]
+macro_rules! default_codec {
+    ($codec:ident) => {
+        #[doc(hidden)]
+        pub(crate) mod __nitinol_default_codec {
+            pub use $crate::codec::$codec::{decode, encode};
+        }
+    };
+}
+
+#[cfg(feature = "json")]
+pub mod json {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use crate::errors::{DeserializeError, SerializeError};
+
+    pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, SerializeError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DeserializeError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[cfg(feature = "cbor")]
+pub mod cbor {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use crate::errors::{DeserializeError, SerializeError};
+
+    pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, SerializeError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DeserializeError> {
+        Ok(ciborium::from_reader(bytes)?)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+pub mod msgpack {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use crate::errors::{DeserializeError, SerializeError};
+
+    /// Encodes structs as maps, so that fields can be added or reordered.
+    pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, SerializeError> {
+        Ok(rmp_serde::to_vec_named(value)?)
+    }
+
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DeserializeError> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
+
+#[cfg(feature = "bincode")]
+pub mod bincode {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use crate::errors::{DeserializeError, SerializeError};
+
+    /// The format does not describe its fields, any change to the event breaks the stored ones.
+    pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, SerializeError> {
+        Ok(::bincode::serialize(value)?)
+    }
+
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DeserializeError> {
+        Ok(::bincode::deserialize(bytes)?)
+    }
+}
+
+#[cfg(feature = "protobuf")]
+pub mod protobuf {
+    use prost::Message;
+
+    use crate::errors::{DeserializeError, SerializeError};
+
+    /// Unlike the other formats, the event implements [`prost::Message`] rather than serde's traits.
+    pub fn encode<T: Message>(value: &T) -> Result<Vec<u8>, SerializeError> {
+        Ok(value.encode_to_vec())
+    }
+
+    pub fn decode<T: Message + Default>(bytes: &[u8]) -> Result<T, DeserializeError> {
+        T::decode(bytes).map_err(DeserializeError::new)
+    }
+}
@@ -1,2 +1,34 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
 pub trait Command: 'static + Sync + Send {
-}
\ No newline at end of file
+    /// Key identifying this command across redeliveries.
+    ///
+    /// Commands returning an id are handled at most once per aggregate within its deduplication window.
+    /// A duplicate is answered with the event produced the first time instead of being handled again.
+    fn command_id(&self) -> Option<CommandId> {
+        None
+    }
+}
+
+/// Deduplication key of a [`Command`], e.g. a request id supplied by the client.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandId(Arc<str>);
+
+impl CommandId {
+    pub fn new(id: impl Into<String>) -> CommandId {
+        Self(id.into().into())
+    }
+}
+
+impl Display for CommandId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for CommandId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
@@ -4,6 +4,12 @@ use std::error::Error;
 #[error("Failed to serialize event: {0}")]
 pub struct SerializeError(Box<dyn Error + Sync + Send>);
 
+impl SerializeError {
+    pub fn new(error: impl Into<Box<dyn Error + Sync + Send>>) -> SerializeError {
+        Self(error.into())
+    }
+}
+
 impl<E: serde::ser::Error + Sync + Send + 'static> From<E> for SerializeError {
     fn from(value: E) -> Self {
         Self(Box::new(value))
@@ -14,8 +20,26 @@ impl<E: serde::ser::Error + Sync + Send + 'static> From<E> for SerializeError {
 #[error("Failed to deserialize event: {0}")]
 pub struct DeserializeError(Box<dyn Error + Sync + Send>);
 
+impl DeserializeError {
+    pub fn new(error: impl Into<Box<dyn Error + Sync + Send>>) -> DeserializeError {
+        Self(error.into())
+    }
+}
+
 impl<E: serde::de::Error + Sync + Send + 'static> From<E> for DeserializeError {
     fn from(value: E) -> Self {
         Self(Box::new(value))
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseIdError {
+    #[error("`{id}` is not in the `{expected}` namespace")]
+    Namespace { id: String, expected: &'static str },
+    #[error("`{id}` is not a valid id: {source}")]
+    Value {
+        id: String,
+        #[source]
+        source: Box<dyn Error + Sync + Send>,
+    },
+}
@@ -9,6 +9,13 @@ use crate::errors::{DeserializeError, SerializeError};
 /// or persisted in a database for aggregates restoration.
 pub trait Event: 'static + Sync + Send + Sized {
     const EVENT_TYPE: &'static str;
+    /// Every key this event is stored under, see [`Event::event_type`].
+    const EVENT_TYPES: &'static [&'static str] = &[Self::EVENT_TYPE];
     fn as_bytes(&self) -> Result<Vec<u8>, SerializeError>;
     fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError>;
+
+    /// Key this event is stored under, [`Event::EVENT_TYPE`] unless it is a variant with a key of its own.
+    fn event_type(&self) -> &'static str {
+        Self::EVENT_TYPE
+    }
 }
@@ -2,41 +2,60 @@ use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+use crate::errors::ParseIdError;
+
 /// Low clone cost identifier that provides a common identification feature within Nitinol.
 /// 
 /// It is a wrapper around `Arc<str>`.
 /// 
 /// If Display is implemented, 
 /// [`ToEntityId::to_entity_id`] and [`IntoEntityId::into_entity_id`] are automatically derived.
-pub struct EntityId(Arc<str>);
+pub struct EntityId {
+    id: Arc<str>,
+}
 
 impl EntityId {
     pub fn new(id: String) -> EntityId {
-        Self(id.into())
+        Self { id: id.into() }
+    }
+
+    /// Id of `typed`, its value prefixed by its namespace as in `order:42`.
+    pub fn typed<T: TypedId>(typed: &T) -> EntityId {
+        Self::new(format!("{}{NAMESPACE_SEPARATOR}{}", T::NAMESPACE, typed.value()))
+    }
+
+    /// Whether the text of this id parses as a `T`, however the id was made.
+    pub fn is<T: TypedId>(&self) -> bool {
+        self.parse::<T>().is_ok()
+    }
+
+    /// Parse back the [`TypedId`] this id was made from.
+    pub fn parse<T: TypedId>(&self) -> Result<T, ParseIdError> {
+        T::from_entity_id(self)
     }
 }
 
 impl Clone for EntityId {
     fn clone(&self) -> Self {
-        Self(Arc::clone(&self.0))
+        Self { id: Arc::clone(&self.id) }
     }
 }
 
 impl Debug for EntityId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.id)
     }
 }
 
 impl Display for EntityId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.id)
     }
 }
 
 impl AsRef<str> for EntityId {
     fn as_ref(&self) -> &str {
-        &self.0
+        &self.id
     }
 }
 
@@ -44,13 +63,13 @@ impl Eq for EntityId {}
 
 impl PartialEq<Self> for EntityId {
     fn eq(&self, other: &Self) -> bool {
-        self.0.eq(&other.0)
+        self.id.eq(&other.id)
     }
 }
 
 impl Hash for EntityId {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.hash(state);
+        self.id.hash(state);
     }
 }
 
@@ -73,3 +92,20 @@ impl<T: ToString + Sync + Send + 'static + ?Sized> ToEntityId for T {
         EntityId::new(self.to_string())
     }
 }
+
+/// Separates the namespace of a [`TypedId`] from its value in the [`EntityId`] made from it.
+pub const NAMESPACE_SEPARATOR: char = ':';
+
+/// Identifier of one kind of aggregate, usually a newtype implementing it with `#[derive(EntityId)]`.
+///
+/// The [`EntityId`] made from it by [`EntityId::typed`] is prefixed by its namespace, as in `order:42`,
+/// so that it never equals the one of another kind of aggregate with the same value. Registries and journals,
+/// which are keyed by [`EntityId`], keep them apart without knowing about namespaces.
+pub trait TypedId: Sized + 'static + Sync + Send {
+    const NAMESPACE: &'static str;
+
+    /// Text of the value, following the namespace in the [`EntityId`].
+    fn value(&self) -> String;
+
+    fn from_entity_id(id: &EntityId) -> Result<Self, ParseIdError>;
+}
@@ -5,3 +5,5 @@ pub mod command;
 pub mod event;
 
 pub mod identifier;
+
+pub mod codec;
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{Attribute, FnArg, ImplItem, ImplItemFn, ItemImpl, ReturnType, Type};
+
+/// Methods of `Process`, moved to its impl rather than kept inherent.
+const PROCESS_METHODS: &[&str] = &["aggregate_id", "start", "stop", "supervise", "terminated", "as_ref_self"];
+
+pub fn expand(attr: TokenStream, mut item: ItemImpl) -> syn::Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(attr, "`#[aggregate]` takes no arguments"));
+    }
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(syn::Error::new_spanned(path, "`#[aggregate]` must be used on an inherent impl block"));
+    }
+
+    let mut errors = Vec::new();
+    let mut process = Vec::new();
+    let mut inherent = Vec::new();
+    let mut handlers = Vec::new();
+    let mut applicators = Vec::new();
+    let mut handled = HashMap::new();
+    let mut applied = HashMap::new();
+
+    for impl_item in std::mem::take(&mut item.items) {
+        let ImplItem::Fn(mut method) = impl_item else {
+            inherent.push(impl_item);
+            continue;
+        };
+        let marker = take_marker(&mut method);
+        let result = match marker {
+            Some((Marker::Handles, attr)) => handler(&method, &attr).and_then(|handler| {
+                check_unique(&mut handled, &handler.message, &method, "command")?;
+                handlers.push(handler);
+                Ok(())
+            }),
+            Some((Marker::Applies, attr)) => applicator(&method, &attr).and_then(|applicator| {
+                check_unique(&mut applied, &applicator.message, &method, "event")?;
+                applicators.push(applicator);
+                Ok(())
+            }),
+            None if PROCESS_METHODS.contains(&method.sig.ident.to_string().as_str()) => {
+                method.vis = syn::Visibility::Inherited;
+                process.push(method);
+                Ok(())
+            }
+            None => {
+                inherent.push(ImplItem::Fn(method));
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+
+    if !process.iter().any(|method| method.sig.ident == "aggregate_id") {
+        errors.push(syn::Error::new_spanned(&item.self_ty, "`#[aggregate]` requires an `fn aggregate_id(&self) -> EntityId` method"));
+    }
+    if let Some(error) = errors.into_iter().reduce(|mut all, e| {
+        all.combine(e);
+        all
+    }) {
+        return Err(error);
+    }
+
+    let self_ty = &item.self_ty;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+    let handler_impls = handlers.iter().map(|Handler { message, event, rejection, method }| quote! {
+        #[::nitinol::__private::async_trait]
+        impl #impl_generics ::nitinol::process::CommandHandler<#message> for #self_ty #where_clause {
+            type Event = #event;
+            type Rejection = #rejection;
+            #method
+        }
+    });
+    let applicator_impls = applicators.iter().map(|Applicator { message, method }| quote! {
+        #[::nitinol::__private::async_trait]
+        impl #impl_generics ::nitinol::process::EventApplicator<#message> for #self_ty #where_clause {
+            #method
+        }
+    });
+
+    // Every event a command can produce must be applicable, or the process could never apply it.
+    let applied_checks = handlers.iter().map(|Handler { event, .. }| quote_spanned! { event.span()=>
+        applied::<#self_ty, #event>();
+    });
+
+    let attrs = &item.attrs;
+    let inherent_impl = (!inherent.is_empty()).then(|| quote! {
+        #(#attrs)*
+        impl #impl_generics #self_ty #where_clause {
+            #(#inherent)*
+        }
+    });
+
+    Ok(quote! {
+        #[::nitinol::__private::async_trait]
+        impl #impl_generics ::nitinol::process::Process for #self_ty #where_clause {
+            #(#process)*
+        }
+
+        #(#handler_impls)*
+        #(#applicator_impls)*
+
+        const _: () = {
+            fn applied<T: ::nitinol::process::EventApplicator<E>, E: ::nitinol::Event>() {}
+            #[allow(dead_code)]
+            fn check #impl_generics () #where_clause {
+                #(#applied_checks)*
+            }
+        };
+
+        #inherent_impl
+    })
+}
+
+enum Marker {
+    Handles,
+    Applies,
+}
+
+/// Removes the `#[handles]` or `#[applies]` attribute of `method`, also when written as a path such as `#[nitinol::handles]`.
+fn take_marker(method: &mut ImplItemFn) -> Option<(Marker, Attribute)> {
+    let marker = |attr: &Attribute| match attr.path().segments.last() {
+        Some(segment) if segment.ident == "handles" => Some(Marker::Handles),
+        Some(segment) if segment.ident == "applies" => Some(Marker::Applies),
+        _ => None,
+    };
+    let position = method.attrs.iter().position(|attr| marker(attr).is_some())?;
+    let attr = method.attrs.remove(position);
+    Some((marker(&attr)?, attr))
+}
+
+fn check_unique(seen: &mut HashMap<String, syn::Ident>, message: &Type, method: &ImplItemFn, kind: &str) -> syn::Result<()> {
+    let key = message.to_token_stream().to_string();
+    if let Some(previous) = seen.insert(key, method.sig.ident.clone()) {
+        return Err(syn::Error::new_spanned(
+            &method.sig.ident,
+            format!("{kind} `{}` is already handled by `{previous}`", message.to_token_stream()),
+        ));
+    }
+    Ok(())
+}
+
+struct Handler {
+    message: Type,
+    event: Type,
+    rejection: Type,
+    method: TokenStream,
+}
+
+struct Applicator {
+    message: Type,
+    method: TokenStream,
+}
+
+/// `async fn (&self, command: C[, ctx: &mut Context]) -> Result<Event, Rejection>`
+fn handler(method: &ImplItemFn, attr: &Attribute) -> syn::Result<Handler> {
+    let (message, pattern, context) = signature(method, attr, false, "handles")?;
+    let (event, rejection) = result_types(&method.sig.output)?;
+    let (attrs, block) = (&method.attrs, &method.block);
+    Ok(Handler {
+        method: quote! {
+            #(#attrs)*
+            async fn handle(&self, #pattern: #message, #context) -> Result<Self::Event, Self::Rejection> #block
+        },
+        message,
+        event,
+        rejection,
+    })
+}
+
+/// `async fn (&mut self, event: E[, ctx: &mut Context])`
+fn applicator(method: &ImplItemFn, attr: &Attribute) -> syn::Result<Applicator> {
+    let (message, pattern, context) = signature(method, attr, true, "applies")?;
+    if let ReturnType::Type(_, ty) = &method.sig.output {
+        return Err(syn::Error::new_spanned(ty, "`#[applies]` methods cannot return a value"));
+    }
+    let (attrs, block) = (&method.attrs, &method.block);
+    Ok(Applicator {
+        method: quote! {
+            #(#attrs)*
+            async fn apply(&mut self, #pattern: #message, #context) #block
+        },
+        message,
+    })
+}
+
+/// Type and pattern of the message argument, and the context argument, `_` if omitted.
+fn signature(method: &ImplItemFn, attr: &Attribute, mutable: bool, marker: &str) -> syn::Result<(Type, TokenStream, TokenStream)> {
+    let sig = &method.sig;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(sig.fn_token, format!("`#[{marker}]` methods must be async")));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&sig.generics, format!("`#[{marker}]` methods cannot be generic")));
+    }
+
+    let receiver = if mutable { "&mut self" } else { "&self" };
+    let mut inputs = sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(r)) if r.reference.is_some() && r.mutability.is_some() == mutable => {}
+        Some(arg) => return Err(syn::Error::new_spanned(arg, format!("`#[{marker}]` methods take `{receiver}`"))),
+        None => return Err(syn::Error::new_spanned(&sig.ident, format!("`#[{marker}]` methods take `{receiver}`"))),
+    }
+    let Some(FnArg::Typed(message)) = inputs.next() else {
+        let noun = if mutable { "event" } else { "command" };
+        return Err(syn::Error::new_spanned(&sig.ident, format!("`#[{marker}]` methods take the {noun} after `{receiver}`")));
+    };
+    let context = match inputs.next() {
+        Some(FnArg::Typed(context)) => context.to_token_stream(),
+        Some(arg) => return Err(syn::Error::new_spanned(arg, "expected the context")),
+        None => quote!(_: &mut ::nitinol::process::Context),
+    };
+    if let Some(arg) = inputs.next() {
+        return Err(syn::Error::new_spanned(arg, format!("`#[{marker}]` methods take at most `{receiver}`, the message and the context")));
+    }
+
+    // `#[handles(Command)]` names the type explicitly, which must then match the argument.
+    let ty = match &attr.meta {
+        syn::Meta::Path(_) => (*message.ty).clone(),
+        syn::Meta::List(_) => {
+            let ty = attr.parse_args::<Type>()?;
+            if ty.to_token_stream().to_string() != message.ty.to_token_stream().to_string() {
+                return Err(syn::Error::new_spanned(&message.ty, format!("expected `{}`, as named by `#[{marker}]`", ty.to_token_stream())));
+            }
+            ty
+        }
+        syn::Meta::NameValue(meta) => return Err(syn::Error::new_spanned(meta, format!("expected `#[{marker}]` or `#[{marker}(Type)]`"))),
+    };
+    let pattern = message.pat.to_token_stream();
+    Ok((ty, pattern, context))
+}
+
+/// `Event` and `Rejection` of a `Result<Event, Rejection>` return type.
+fn result_types(output: &ReturnType) -> syn::Result<(Type, Type)> {
+    let expected = "`#[handles]` methods return `Result<Event, Rejection>`";
+    let ReturnType::Type(_, ty) = output else {
+        return Err(syn::Error::new_spanned(output, expected));
+    };
+    let Type::Path(path) = &**ty else {
+        return Err(syn::Error::new_spanned(ty, expected));
+    };
+    let Some(segment) = path.path.segments.last().filter(|segment| segment.ident == "Result") else {
+        return Err(syn::Error::new_spanned(ty, expected));
+    };
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return Err(syn::Error::new_spanned(ty, expected));
+    };
+    let mut types = arguments.args.iter().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    });
+    match (types.next(), types.next(), types.next()) {
+        (Some(event), Some(rejection), None) => Ok((event, rejection)),
+        _ => Err(syn::Error::new_spanned(ty, expected)),
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use darling::ast::Data;
+use darling::util::Ignored;
+use darling::{Error, FromDeriveInput, FromVariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(persist), supports(struct_any, enum_any))]
+struct PersistAttribute {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: Data<PersistVariant, Ignored>,
+    key: Option<syn::LitStr>,
+    codec: Option<syn::LitStr>,
+    enc: Option<syn::LitStr>,
+    dec: Option<syn::LitStr>,
+    /// Where clause of the impl, replacing the default `'static + Sync + Send` bound of every type parameter.
+    bound: Option<syn::LitStr>,
+}
+
+#[derive(FromVariant)]
+#[darling(attributes(persist))]
+struct PersistVariant {
+    ident: syn::Ident,
+    key: Option<syn::LitStr>,
+}
+
+/// Formats of `#[persist(codec = "...")]`, with the `nitinol-core` feature enabling each.
+const CODECS: &[&str] = &["json", "cbor", "msgpack", "bincode", "protobuf"];
+
+pub fn derive(input: &syn::DeriveInput) -> darling::Result<TokenStream> {
+    if let syn::Data::Union(data) = &input.data {
+        return Err(Error::custom("`Event` cannot be derived for unions").with_span(&data.union_token));
+    }
+    let attr = PersistAttribute::from_derive_input(input)?;
+    let mut errors = Error::accumulator();
+
+    let event_type = match &attr.key {
+        Some(key) => errors.handle(validate_key(key)).unwrap_or_default(),
+        None => to_kebab_case(&attr.ident.to_string()),
+    };
+    let bodies = errors.handle(persist_bodies(&attr).map_err(Error::from));
+    let where_clause = errors.handle(where_clause(&attr));
+    let variants = errors.handle(variant_keys(&attr));
+    errors.finish()?;
+
+    let (enc, dec) = bodies.expect("checked by the accumulator");
+    let where_clause = where_clause.expect("checked by the accumulator");
+    let input_name = &attr.ident;
+    let (impl_generics, ty_generics, _) = attr.generics.split_for_impl();
+
+    let variant_keys = variants.flatten().map(|(variants, keys)| quote! {
+        const EVENT_TYPES: &'static [&'static str] = &[#(#keys),*];
+
+        fn event_type(&self) -> &'static str {
+            match self {
+                #(Self::#variants { .. } => #keys,)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::nitinol::Event for #input_name #ty_generics #where_clause {
+            const EVENT_TYPE: &'static str = #event_type;
+            fn as_bytes(&self) -> Result<Vec<u8>, ::nitinol::errors::SerializeError> {
+                #enc
+            }
+            fn from_bytes(bytes: &[u8]) -> Result<Self, ::nitinol::errors::DeserializeError> {
+                #dec
+            }
+            #variant_keys
+        }
+    })
+}
+
+/// Bodies of `as_bytes` and `from_bytes`.
+///
+/// Without `codec` nor `enc`/`dec`, they call the codec set by `nitinol::default_codec!` at the root of the crate.
+fn persist_bodies(attr: &PersistAttribute) -> syn::Result<(syn::Expr, syn::Expr)> {
+    match (&attr.codec, &attr.enc, &attr.dec) {
+        (Some(codec), None, None) => {
+            if !CODECS.contains(&codec.value().as_str()) {
+                return Err(syn::Error::new_spanned(codec, format!("unknown codec, expected one of {}", CODECS.join(", "))));
+            }
+            let module = syn::Ident::new(&codec.value(), codec.span());
+            Ok((
+                syn::parse_quote!(::nitinol::codec::#module::encode(self)),
+                syn::parse_quote!(::nitinol::codec::#module::decode(bytes)),
+            ))
+        }
+        (Some(codec), _, _) => Err(syn::Error::new_spanned(codec, "`codec` cannot be combined with `enc` and `dec`")),
+        (None, Some(enc), Some(dec)) => {
+            // User functions may return any error convertible by `?`.
+            let enc = parse_function(enc, "enc")?;
+            let dec = parse_function(dec, "dec")?;
+            Ok((syn::parse_quote!(Ok(#enc(self)?)), syn::parse_quote!(Ok(#dec(bytes)?))))
+        }
+        (None, Some(enc), None) => Err(syn::Error::new_spanned(enc, "`enc` requires `dec`")),
+        (None, None, Some(dec)) => Err(syn::Error::new_spanned(dec, "`dec` requires `enc`")),
+        (None, None, None) => Ok((
+            syn::parse_quote!(crate::__nitinol_default_codec::encode(self)),
+            syn::parse_quote!(crate::__nitinol_default_codec::decode(bytes)),
+        )),
+    }
+}
+
+fn parse_function(lit: &syn::LitStr, name: &str) -> syn::Result<syn::Expr> {
+    lit.parse::<syn::Expr>().map_err(|e| {
+        syn::Error::new(lit.span(), format!("`{name}` must be a path to a function, such as `serde_json::to_vec`: {e}"))
+    })
+}
+
+fn where_clause(attr: &PersistAttribute) -> darling::Result<syn::WhereClause> {
+    let mut where_clause = attr.generics.where_clause.clone().unwrap_or_else(|| syn::parse_quote!(where));
+    match &attr.bound {
+        Some(bound) => {
+            let predicates = bound
+                .parse_with(syn::punctuated::Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated)
+                .map_err(|e| Error::custom(format!("`bound` must be a list of where predicates: {e}")).with_span(bound))?;
+            where_clause.predicates.extend(predicates);
+        }
+        None => {
+            for param in attr.generics.type_params() {
+                let ident = &param.ident;
+                where_clause.predicates.push(syn::parse_quote!(#ident: 'static + Sync + Send));
+            }
+        }
+    }
+    Ok(where_clause)
+}
+
+/// Variants of an enum and their keys, `None` for structs and enums without a `key` on any variant.
+///
+/// Variants without one are stored under their name in kebab-case.
+fn variant_keys(attr: &PersistAttribute) -> darling::Result<Option<(Vec<&syn::Ident>, Vec<String>)>> {
+    let Data::Enum(variants) = &attr.data else {
+        return Ok(None);
+    };
+    if variants.iter().all(|variant| variant.key.is_none()) {
+        return Ok(None);
+    }
+
+    let mut errors = Error::accumulator();
+    let mut seen = HashMap::new();
+    let mut keys = Vec::with_capacity(variants.len());
+    for variant in variants {
+        let key = match &variant.key {
+            Some(lit) => errors.handle(validate_key(lit)).unwrap_or_default(),
+            None => to_kebab_case(&variant.ident.to_string()),
+        };
+        if let Some(previous) = seen.insert(key.clone(), &variant.ident) {
+            let span = variant.key.as_ref().map_or(variant.ident.span(), syn::LitStr::span);
+            errors.push(Error::custom(format!("key `{key}` is already used by variant `{previous}`")).with_span(&span));
+        }
+        keys.push(key);
+    }
+    errors.finish_with(Some((variants.iter().map(|variant| &variant.ident).collect(), keys)))
+}
+
+/// Keys end up in storage as the registry key of payloads, so they are limited to ASCII
+/// alphanumerics and `-`, `_`, `.`, `:`.
+fn validate_key(lit: &syn::LitStr) -> darling::Result<String> {
+    let key = lit.value();
+    if key.is_empty() {
+        return Err(Error::custom("key cannot be empty").with_span(lit));
+    }
+    if let Some(c) = key.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))) {
+        return Err(Error::custom(format!("invalid character {c:?} in key, expected ASCII alphanumerics, `-`, `_`, `.` or `:`")).with_span(lit));
+    }
+    Ok(key)
+}
+
+pub fn to_kebab_case(input: &str) -> String {
+    input.chars().fold(String::new(), |mut acc, c| {
+        if c.is_uppercase() {
+            if !acc.is_empty() {
+                acc.push('-');
+            }
+            acc.push(c.to_ascii_lowercase());
+        } else {
+            acc.push(c);
+        }
+        acc
+    })
+}
@@ -0,0 +1,124 @@
+use darling::FromDeriveInput;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::event::to_kebab_case;
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(entity))]
+struct EntityAttribute {
+    namespace: Option<syn::LitStr>,
+    /// Implement `Display` with the text of the id, instead of converting to `EntityId` directly.
+    #[darling(default)]
+    display: bool,
+}
+
+pub fn derive(input: &syn::DeriveInput) -> darling::Result<TokenStream> {
+    let attr = EntityAttribute::from_derive_input(input)?;
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(darling::Error::custom("typed ids cannot be generic").with_span(&input.generics));
+    }
+    let (member, ty) = newtype_field(input)?;
+    let namespace = match &attr.namespace {
+        Some(lit) => validate_namespace(lit)?,
+        None => default_namespace(&name.to_string()),
+    };
+
+    // Any `Display` type converts to `EntityId` through it, so the direct conversion is only possible without one.
+    let conversion = match attr.display {
+        true => quote! {
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    write!(f, "{}{}{}", #namespace, ::nitinol::NAMESPACE_SEPARATOR, self.#member)
+                }
+            }
+        },
+        false => quote! {
+            impl ::nitinol::ToEntityId for #name {
+                fn to_entity_id(&self) -> ::nitinol::EntityId {
+                    ::nitinol::EntityId::typed(self)
+                }
+            }
+
+            impl ::nitinol::IntoEntityId for #name {
+                fn into_entity_id(self) -> ::nitinol::EntityId {
+                    ::nitinol::EntityId::typed(&self)
+                }
+            }
+        },
+    };
+
+    Ok(quote! {
+        #conversion
+
+        impl ::nitinol::TypedId for #name {
+            const NAMESPACE: &'static str = #namespace;
+
+            fn value(&self) -> String {
+                ::std::string::ToString::to_string(&self.#member)
+            }
+
+            fn from_entity_id(id: &::nitinol::EntityId) -> Result<Self, ::nitinol::errors::ParseIdError> {
+                let value = ::std::convert::AsRef::<str>::as_ref(id)
+                    .strip_prefix(#namespace)
+                    .and_then(|rest| rest.strip_prefix(::nitinol::NAMESPACE_SEPARATOR))
+                    .ok_or_else(|| ::nitinol::errors::ParseIdError::Namespace { id: id.to_string(), expected: #namespace })?;
+                let value = value.parse::<#ty>()
+                    .map_err(|e| ::nitinol::errors::ParseIdError::Value { id: id.to_string(), source: e.into() })?;
+                Ok(Self { #member: value })
+            }
+        }
+
+        impl ::std::str::FromStr for #name {
+            type Err = ::nitinol::errors::ParseIdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <Self as ::nitinol::TypedId>::from_entity_id(&::nitinol::EntityId::new(s.to_string()))
+            }
+        }
+
+        impl ::std::convert::TryFrom<&::nitinol::EntityId> for #name {
+            type Error = ::nitinol::errors::ParseIdError;
+
+            fn try_from(id: &::nitinol::EntityId) -> Result<Self, Self::Error> {
+                <Self as ::nitinol::TypedId>::from_entity_id(id)
+            }
+        }
+    })
+}
+
+/// The only field of a newtype, which holds the value of the id.
+fn newtype_field(input: &syn::DeriveInput) -> darling::Result<(syn::Member, &syn::Type)> {
+    let expected = "`EntityId` can only be derived for a struct with a single field, such as `struct OrderId(u64)`";
+    let syn::Data::Struct(data) = &input.data else {
+        return Err(darling::Error::custom(expected).with_span(&input.ident));
+    };
+    let mut fields = data.fields.iter();
+    let (Some(field), None) = (fields.next(), fields.next()) else {
+        return Err(darling::Error::custom(expected).with_span(&input.ident));
+    };
+    let member = match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(0.into()),
+    };
+    Ok((member, &field.ty))
+}
+
+/// Name of the type in kebab-case, without an `Id` suffix, so that `OrderId` is in `order`.
+fn default_namespace(name: &str) -> String {
+    let name = name.strip_suffix("Id").filter(|name| !name.is_empty()).unwrap_or(name);
+    to_kebab_case(name)
+}
+
+/// The namespace is separated from the value by `:`, so it is limited to ASCII alphanumerics and `-`, `_`, `.`.
+fn validate_namespace(lit: &syn::LitStr) -> darling::Result<String> {
+    let namespace = lit.value();
+    if namespace.is_empty() {
+        return Err(darling::Error::custom("namespace cannot be empty").with_span(lit));
+    }
+    if let Some(c) = namespace.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))) {
+        return Err(darling::Error::custom(format!("invalid character {c:?} in namespace, expected ASCII alphanumerics, `-`, `_` or `.`")).with_span(lit));
+    }
+    Ok(namespace)
+}
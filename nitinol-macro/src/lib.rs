@@ -1,71 +1,137 @@
+mod aggregate;
+mod event;
+mod id;
+mod resolve;
+
 use proc_macro::TokenStream;
-use darling::FromDeriveInput;
 use quote::quote;
 
-#[proc_macro_derive(Command)]
+#[proc_macro_derive(Command, attributes(command))]
 pub fn derive_command(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
     let input_name = &input.ident;
     
-    let token = quote! {
-        impl ::nitinol::Command for #input_name {}
-    };
-    
-    token.into()
-}
-
-#[derive(FromDeriveInput)]
-#[darling(attributes(persist))]
-struct PersistAttribute {
-    #[darling(default)]
-    key: String,
-    enc: String,
-    dec: String
-}
-
-#[proc_macro_derive(Event, attributes(persist))]
-pub fn derive_event(input: TokenStream) -> TokenStream {
-    let input = syn::parse_macro_input!(input as syn::DeriveInput);
-    let attr = match PersistAttribute::from_derive_input(&input) {
+    let id = match command_id_field(&input) {
         Ok(v) => v,
-        Err(e) => return e.write_errors().into()
-    };
-    let input_name = &input.ident;
-    
-    let event_type = if attr.key.is_empty() {
-        to_kebab_case(&input_name.to_string())
-    } else {
-        attr.key
+        Err(e) => return e.to_compile_error().into()
     };
     
-    let enc = syn::parse_str::<syn::Expr>(&attr.enc).unwrap();
-    let dec = syn::parse_str::<syn::Expr>(&attr.dec).unwrap();
+    let body = id.map(|field| quote! {
+        fn command_id(&self) -> Option<::nitinol::CommandId> {
+            Some(::nitinol::CommandId::new(self.#field.to_string()))
+        }
+    });
     
     let token = quote! {
-        impl ::nitinol::Event for #input_name {
-            const EVENT_TYPE: &'static str = #event_type;
-            fn as_bytes(&self) -> Result<Vec<u8>, ::nitinol::errors::SerializeError> {
-                Ok(#enc(self)?)
-            }
-            fn from_bytes(bytes: &[u8]) -> Result<Self, ::nitinol::errors::DeserializeError> {
-                Ok(#dec(bytes)?)
-            }
+        impl ::nitinol::Command for #input_name {
+            #body
         }
     };
     
     token.into()
 }
 
-fn to_kebab_case(input: &str) -> String {
-    input.chars().fold(String::new(), |mut acc, c| {
-        if c.is_uppercase() {
-            if !acc.is_empty() {
-                acc.push('-');
+/// Finds the struct field marked with `#[command(id)]`.
+fn command_id_field(input: &syn::DeriveInput) -> syn::Result<Option<syn::Member>> {
+    let syn::Data::Struct(data) = &input.data else {
+        return Ok(None);
+    };
+    
+    let mut found = None;
+    for (index, field) in data.fields.iter().enumerate() {
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("command")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `id`"))
+                }
+            })?;
+            if found.is_some() {
+                return Err(syn::Error::new_spanned(attr, "only one field can be marked with `#[command(id)]`"));
             }
-            acc.push(c.to_ascii_lowercase());
-        } else {
-            acc.push(c);
+            found = Some(match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(index.into()),
+            });
         }
-        acc
-    })
+    }
+    Ok(found)
+}
+
+/// Implements `Event`, stored under `#[persist(key = "...")]` or the name of the type in kebab-case.
+///
+/// Variants of an enum given a `#[persist(key = "...")]` are stored under their own key, and the others
+/// under their name in kebab-case. Types sharing a key can only be detected once they are registered
+/// in the same `Mapper`, which panics naming both of them.
+#[proc_macro_derive(Event, attributes(persist))]
+pub fn derive_event(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match event::derive(&input) {
+        Ok(token) => token.into(),
+        Err(e) => e.write_errors().into()
+    }
+}
+
+/// Implements `Process`, `CommandHandler` and `EventApplicator` from the methods of an impl block.
+///
+/// - `aggregate_id`, `start`, `stop` and the other methods of `Process` implement it.
+/// - `#[handles]` methods, `async fn(&self, C[, &mut Context]) -> Result<Event, Rejection>`, implement `CommandHandler<C>`.
+/// - `#[applies]` methods, `async fn(&mut self, E[, &mut Context])`, implement `EventApplicator<E>`.
+/// - Other items are kept in an inherent impl.
+///
+/// The events returned by `#[handles]` methods must be applicable, which is checked at compile time.
+#[proc_macro_attribute]
+pub fn aggregate(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemImpl);
+    match aggregate::expand(attr.into(), item) {
+        Ok(token) => token.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+/// Marks a command handler of an `#[aggregate]` impl block.
+#[proc_macro_attribute]
+pub fn handles(_: TokenStream, item: TokenStream) -> TokenStream {
+    outside_aggregate("handles", item)
+}
+
+/// Marks an event applicator of an `#[aggregate]` impl block.
+#[proc_macro_attribute]
+pub fn applies(_: TokenStream, item: TokenStream) -> TokenStream {
+    outside_aggregate("applies", item)
+}
+
+/// `#[aggregate]` removes the markers of its methods, so they only expand when misplaced.
+fn outside_aggregate(marker: &str, item: TokenStream) -> TokenStream {
+    let item = proc_macro2::TokenStream::from(item);
+    let error = syn::Error::new_spanned(&item, format!("`#[{marker}]` must be used on a method of an `#[aggregate]` impl block"));
+    error.to_compile_error().into()
+}
+
+/// Implements `ResolveMapping`, registering each `Event = Handler` pair of `#[resolve(...)]`.
+#[proc_macro_derive(ResolveMapping, attributes(resolve))]
+pub fn derive_resolve_mapping(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match resolve::derive(&input) {
+        Ok(token) => token.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+/// Implements `TypedId` for a newtype, its conversions to an `EntityId` prefixed by its namespace,
+/// and `FromStr` and `TryFrom<&EntityId>` parsing it back.
+///
+/// The namespace is given by `#[entity(namespace = "...")]`, and defaults to the name of the type
+/// in kebab-case without its `Id` suffix.
+///
+/// `#[entity(display)]` implements `Display` with the prefixed text instead of the conversions,
+/// which every `Display` type then gets through it, so both modes make the same ids.
+#[proc_macro_derive(EntityId, attributes(entity))]
+pub fn derive_entity_id(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match id::derive(&input) {
+        Ok(token) => token.into(),
+        Err(e) => e.write_errors().into()
+    }
 }
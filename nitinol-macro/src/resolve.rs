@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+/// `Event = Handler` of `#[resolve(...)]`.
+struct Resolve {
+    event: syn::Type,
+    handler: syn::Path,
+}
+
+impl Parse for Resolve {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let event = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let handler = input.parse()?;
+        Ok(Self { event, handler })
+    }
+}
+
+pub fn derive(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let mut resolves = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("resolve")) {
+        resolves.extend(attr.parse_args_with(Punctuated::<Resolve, syn::Token![,]>::parse_terminated)?);
+    }
+    if resolves.is_empty() {
+        return Err(syn::Error::new_spanned(&input.ident, "expected at least one `#[resolve(Event = Handler)]`"));
+    }
+
+    let mut seen = HashSet::new();
+    for Resolve { event, handler } in &resolves {
+        let key = (event.to_token_stream().to_string(), handler.to_token_stream().to_string());
+        if !seen.insert(key) {
+            return Err(syn::Error::new_spanned(
+                handler,
+                format!("`{}` is already resolved by `{}`", event.to_token_stream(), handler.to_token_stream()),
+            ));
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // Spanned on the pair, so that a handler the type does not support is reported there.
+    let registers = resolves.iter().map(|Resolve { event, handler }| quote_spanned! { event.span()=>
+        mapper.register::<#event, #handler>();
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::nitinol::resolver::mapping::ResolveMapping for #name #ty_generics #where_clause {
+            fn mapping(mapper: &mut ::nitinol::resolver::mapping::Mapper<Self>) {
+                #(#registers)*
+            }
+        }
+    })
+}
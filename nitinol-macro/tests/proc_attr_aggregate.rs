@@ -0,0 +1,84 @@
+use nitinol::process::manager::ProcessManager;
+use nitinol::process::Context;
+use nitinol::{aggregate, Command, EntityId, Event, ToEntityId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Command)]
+pub enum AccountCommand {
+    Deposit(u32),
+    Withdraw(u32),
+}
+
+#[derive(Debug, Command)]
+pub struct Close;
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "json")]
+pub enum AccountEvent {
+    Deposited(u32),
+    Withdrawn(u32),
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "json")]
+pub struct Closed;
+
+#[derive(Debug, Default)]
+pub struct Account {
+    balance: u32,
+    pub closed: bool,
+}
+
+#[aggregate]
+impl Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+
+    #[handles]
+    async fn handle(&self, command: AccountCommand) -> Result<AccountEvent, String> {
+        match command {
+            AccountCommand::Deposit(amount) => Ok(AccountEvent::Deposited(amount)),
+            AccountCommand::Withdraw(amount) if amount <= self.balance => Ok(AccountEvent::Withdrawn(amount)),
+            AccountCommand::Withdraw(_) => Err(self.rejection()),
+        }
+    }
+
+    #[handles(Close)]
+    async fn close(&self, _: Close, _ctx: &mut Context) -> Result<Closed, String> {
+        Ok(Closed)
+    }
+
+    #[applies]
+    async fn apply(&mut self, event: AccountEvent) {
+        match event {
+            AccountEvent::Deposited(amount) => self.balance += amount,
+            AccountEvent::Withdrawn(amount) => self.balance -= amount,
+        }
+    }
+
+    #[applies]
+    async fn closed(&mut self, _: Closed, _: &mut Context) {
+        self.closed = true;
+    }
+
+    fn rejection(&self) -> String {
+        format!("insufficient balance {}", self.balance)
+    }
+}
+
+#[tokio::test]
+async fn aggregate_handles_and_applies() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Account::default(), 0).await.unwrap();
+
+    let event = refs.handle(AccountCommand::Deposit(10)).await.unwrap().unwrap();
+    assert_eq!(event, AccountEvent::Deposited(10));
+    refs.apply(event).await.unwrap();
+
+    let rejection = refs.handle(AccountCommand::Withdraw(20)).await.unwrap().unwrap_err();
+    assert_eq!(rejection, "insufficient balance 10");
+
+    let event = refs.handle(Close).await.unwrap().unwrap();
+    refs.apply(event).await.unwrap();
+}
@@ -4,4 +4,24 @@ use nitinol_macro::Command;
 pub struct DomainStructCommand {}
 
 #[derive(Command)]
-pub enum DomainEnumCommand {}
\ No newline at end of file
+pub enum DomainEnumCommand {}
+
+#[derive(Command)]
+pub struct IdentifiedCommand {
+    #[command(id)]
+    pub request_id: String,
+    pub amount: u64,
+}
+
+#[derive(Command)]
+pub struct IdentifiedTupleCommand(pub u64, #[command(id)] pub u128);
+
+#[test]
+fn command_id_is_read_from_marked_field() {
+    use nitinol::Command;
+
+    let command = IdentifiedCommand { request_id: "req-1".to_string(), amount: 10 };
+    assert_eq!(command.command_id(), Some(nitinol::CommandId::new("req-1")));
+    assert_eq!(IdentifiedTupleCommand(1, 2).command_id(), Some(nitinol::CommandId::new("2")));
+    assert_eq!(DomainStructCommand {}.command_id(), None);
+}
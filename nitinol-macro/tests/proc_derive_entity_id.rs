@@ -0,0 +1,136 @@
+use nitinol::errors::ParseIdError;
+use nitinol::process::manager::ProcessManager;
+use nitinol::process::router::Strategy;
+use nitinol::process::Process;
+use nitinol::{EntityId, ToEntityId, TypedId};
+
+#[derive(Debug, Clone, Copy, PartialEq, EntityId)]
+pub struct OrderId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, EntityId)]
+pub struct UserId(u64);
+
+#[derive(Debug, Clone, PartialEq, EntityId)]
+#[entity(namespace = "billing.invoice")]
+pub struct InvoiceNumber {
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, EntityId)]
+#[entity(display)]
+pub struct ShipmentId(u64);
+
+#[test]
+fn ids_are_prefixed_by_their_namespace() {
+    assert_eq!(OrderId::NAMESPACE, "order");
+    assert_eq!(OrderId(1).to_entity_id().as_ref(), "order:1");
+    assert_eq!(UserId(1).to_entity_id().as_ref(), "user:1");
+    assert_ne!(OrderId(1).to_entity_id(), UserId(1).to_entity_id());
+
+    let invoice = InvoiceNumber { value: "2025:001".to_string() };
+    assert_eq!(invoice.to_entity_id().as_ref(), "billing.invoice:2025:001");
+    assert!(invoice.to_entity_id().is::<InvoiceNumber>());
+}
+
+#[test]
+fn ids_are_parsed_back() {
+    let id = OrderId(42).to_entity_id();
+    assert!(id.is::<OrderId>());
+    assert!(!id.is::<UserId>());
+    assert_eq!(id.parse::<OrderId>().unwrap(), OrderId(42));
+    assert_eq!(OrderId::try_from(&id).unwrap(), OrderId(42));
+    assert_eq!("order:42".parse::<OrderId>().unwrap(), OrderId(42));
+
+    let invoice = InvoiceNumber { value: "2025:001".to_string() };
+    assert_eq!(invoice.to_entity_id().parse::<InvoiceNumber>().unwrap(), invoice);
+}
+
+#[test]
+fn ids_of_another_namespace_are_rejected() {
+    let error = UserId(42).to_entity_id().parse::<OrderId>().unwrap_err();
+    assert!(matches!(error, ParseIdError::Namespace { expected: "order", .. }));
+    assert!(matches!("42".parse::<OrderId>(), Err(ParseIdError::Namespace { .. })));
+    assert!(matches!("order:abc".parse::<OrderId>(), Err(ParseIdError::Value { .. })));
+    assert!(!EntityId::new("42".to_string()).is::<OrderId>());
+}
+
+#[test]
+fn ids_are_typed_by_their_text() {
+    let raw = EntityId::new("order:1".to_string());
+    assert_eq!(raw, OrderId(1).to_entity_id());
+    assert!(raw.is::<OrderId>());
+    assert_eq!(raw.parse::<OrderId>().unwrap(), OrderId(1));
+}
+
+#[test]
+fn display_makes_the_same_ids() {
+    assert_eq!(ShipmentId(7).to_string(), "shipment:7");
+    assert_eq!(ShipmentId(7).to_entity_id(), EntityId::typed(&ShipmentId(7)));
+    assert!(ShipmentId(7).to_entity_id().is::<ShipmentId>());
+    assert_eq!("shipment:7".parse::<ShipmentId>().unwrap(), ShipmentId(7));
+}
+
+pub struct Order(OrderId);
+
+impl Process for Order {
+    fn aggregate_id(&self) -> EntityId {
+        self.0.to_entity_id()
+    }
+}
+
+pub struct User(UserId);
+
+impl Process for User {
+    fn aggregate_id(&self) -> EntityId {
+        self.0.to_entity_id()
+    }
+}
+
+#[tokio::test]
+async fn registry_keeps_namespaces_apart() {
+    let system = ProcessManager::default();
+    system.spawn(Order(OrderId(1)), 0).await.unwrap();
+    system.spawn(Order(OrderId(2)), 0).await.unwrap();
+    system.spawn(User(UserId(1)), 0).await.unwrap();
+
+    assert!(system.find::<Order>(OrderId(1)).await.unwrap().is_some());
+    assert!(system.find::<User>(UserId(1)).await.unwrap().is_some());
+    assert!(system.find::<User>(UserId(2)).await.unwrap().is_none());
+
+    let mut orders = system.ids::<OrderId>().await;
+    orders.sort_by_key(|id| id.0);
+    assert_eq!(orders, vec![OrderId(1), OrderId(2)]);
+    assert_eq!(system.ids::<UserId>().await, vec![UserId(1)]);
+}
+
+pub struct Invoice(EntityId);
+
+impl Process for Invoice {
+    fn aggregate_id(&self) -> EntityId {
+        self.0.clone()
+    }
+}
+
+#[tokio::test]
+async fn ids_built_from_text_are_listed() {
+    let system = ProcessManager::default();
+    system.spawn(Invoice(InvoiceNumber { value: "2025:001".to_string() }.to_entity_id()), 0).await.unwrap();
+    system.spawn(Invoice(EntityId::new("billing.invoice:2025:002".to_string())), 0).await.unwrap();
+    system.spawn(Invoice(ShipmentId(7).to_entity_id()), 0).await.unwrap();
+
+    let mut invoices = system.ids::<InvoiceNumber>().await;
+    invoices.sort_by(|a, b| a.value.cmp(&b.value));
+    assert_eq!(invoices, vec![
+        InvoiceNumber { value: "2025:001".to_string() },
+        InvoiceNumber { value: "2025:002".to_string() },
+    ]);
+    assert_eq!(system.ids::<ShipmentId>().await, vec![ShipmentId(7)]);
+}
+
+#[tokio::test]
+async fn routees_are_not_listed() {
+    let system = ProcessManager::default();
+    system.pool(OrderId(3), 2, Strategy::RoundRobin, |id: &EntityId| Invoice(id.clone())).await.unwrap();
+
+    assert!(system.ids::<OrderId>().await.is_empty());
+}
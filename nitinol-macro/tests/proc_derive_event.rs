@@ -22,4 +22,101 @@ pub enum AnotherEvent {
     Event1,
     Event2,
     Event3,
-}
\ No newline at end of file
+}
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "json")]
+pub struct JsonEvent {
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "cbor")]
+pub struct CborEvent {
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "msgpack")]
+pub struct MsgpackEvent {
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "bincode")]
+pub struct BincodeEvent {
+    pub name: String,
+}
+
+#[derive(PartialEq, Event, prost::Message)]
+#[persist(codec = "protobuf")]
+pub struct ProtobufEvent {
+    #[prost(string, tag = "1")]
+    pub name: String,
+}
+
+#[test]
+fn codecs_round_trip() {
+    use nitinol::Event;
+
+    let bytes = JsonEvent { name: "json".to_string() }.as_bytes().unwrap();
+    assert_eq!(bytes, br#"{"name":"json"}"#);
+    assert_eq!(JsonEvent::from_bytes(&bytes).unwrap(), JsonEvent { name: "json".to_string() });
+
+    let bytes = CborEvent { name: "cbor".to_string() }.as_bytes().unwrap();
+    assert_eq!(CborEvent::from_bytes(&bytes).unwrap(), CborEvent { name: "cbor".to_string() });
+
+    let bytes = MsgpackEvent { name: "msgpack".to_string() }.as_bytes().unwrap();
+    assert_eq!(MsgpackEvent::from_bytes(&bytes).unwrap(), MsgpackEvent { name: "msgpack".to_string() });
+
+    let bytes = BincodeEvent { name: "bincode".to_string() }.as_bytes().unwrap();
+    assert_eq!(BincodeEvent::from_bytes(&bytes).unwrap(), BincodeEvent { name: "bincode".to_string() });
+
+    let bytes = ProtobufEvent { name: "protobuf".to_string() }.as_bytes().unwrap();
+    assert_eq!(ProtobufEvent::from_bytes(&bytes).unwrap(), ProtobufEvent { name: "protobuf".to_string() });
+}
+
+#[test]
+fn malformed_bytes_fail_to_deserialize() {
+    use nitinol::Event;
+
+    assert!(JsonEvent::from_bytes(b"{").is_err());
+    assert!(CborEvent::from_bytes(&[0xff]).is_err());
+    assert!(ProtobufEvent::from_bytes(&[0x0a, 0xff]).is_err());
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "json", key = "wrapped", bound = "T: Serialize + serde::de::DeserializeOwned + 'static + Sync + Send")]
+pub struct Wrapped<T> {
+    pub inner: T,
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "json", key = "account.event")]
+pub enum AccountEvent {
+    #[persist(key = "account.opened")]
+    Opened { owner: String },
+    Deposited(u64),
+    Closed,
+}
+
+#[test]
+fn generic_events_are_supported() {
+    use nitinol::Event;
+
+    let event = Wrapped { inner: 42_u64 };
+    assert_eq!(Wrapped::<u64>::EVENT_TYPE, "wrapped");
+    assert_eq!(Wrapped::<u64>::from_bytes(&event.as_bytes().unwrap()).unwrap(), event);
+}
+
+#[test]
+fn variants_have_keys() {
+    use nitinol::Event;
+
+    let event = AccountEvent::Opened { owner: "alice".to_string() };
+    assert_eq!(AccountEvent::EVENT_TYPE, "account.event");
+    assert_eq!(AccountEvent::EVENT_TYPES, &["account.opened", "deposited", "closed"]);
+    assert_eq!(event.event_type(), "account.opened");
+    assert_eq!(AccountEvent::Deposited(10).event_type(), "deposited");
+    assert_eq!(AccountEvent::Closed.event_type(), "closed");
+    assert_eq!(AccountEvent::from_bytes(&event.as_bytes().unwrap()).unwrap(), event);
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use nitinol_macro::Event;
+
+nitinol::default_codec!(json);
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+pub struct Registered {
+    pub email: String,
+}
+
+#[derive(Debug, PartialEq, Event, Deserialize, Serialize)]
+#[persist(codec = "msgpack")]
+pub struct Unregistered {
+    pub email: String,
+}
+
+#[test]
+fn events_without_codec_use_the_default() {
+    use nitinol::Event;
+
+    let event = Registered { email: "alice@example.com".to_string() };
+    let bytes = event.as_bytes().unwrap();
+    assert_eq!(bytes, br#"{"email":"alice@example.com"}"#);
+    assert_eq!(Registered::from_bytes(&bytes).unwrap(), event);
+    assert_eq!(Registered::EVENT_TYPE, "registered");
+}
+
+#[test]
+fn named_codec_overrides_the_default() {
+    use nitinol::Event;
+
+    let event = Unregistered { email: "alice@example.com".to_string() };
+    let bytes = event.as_bytes().unwrap();
+    assert!(serde_json::from_slice::<serde_json::Value>(&bytes).is_err());
+    assert_eq!(Unregistered::from_bytes(&bytes).unwrap(), event);
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use nitinol::projection::Projection;
+use nitinol::projection::resolver::Project;
+use nitinol::resolver::mapping::{Mapper, ResolveMapping as _};
+use nitinol::resolver::resolver::ResolveType;
+use nitinol::{Event, ResolveMapping};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Event, Deserialize, Serialize)]
+#[persist(codec = "json")]
+pub struct Opened;
+
+#[derive(Debug, Event, Deserialize, Serialize)]
+#[persist(codec = "json")]
+pub struct Renamed(String);
+
+#[derive(ResolveMapping)]
+#[resolve(Opened = Project)]
+#[resolve(Renamed = Project)]
+pub struct Profile {
+    pub name: String,
+}
+
+#[async_trait]
+impl Projection<Opened> for Profile {
+    type Rejection = ();
+
+    async fn first(_: Opened) -> Result<Self, Self::Rejection> {
+        Ok(Profile { name: String::new() })
+    }
+
+    async fn apply(&mut self, _: Opened) -> Result<(), Self::Rejection> {
+        Err(())
+    }
+}
+
+#[async_trait]
+impl Projection<Renamed> for Profile {
+    type Rejection = ();
+
+    async fn apply(&mut self, event: Renamed) -> Result<(), Self::Rejection> {
+        self.name = event.0;
+        Ok(())
+    }
+}
+
+#[test]
+fn every_pair_is_registered() {
+    let mut mapper = Mapper::<Profile>::default();
+    Profile::mapping(&mut mapper);
+    for key in [Opened::EVENT_TYPE, Renamed::EVENT_TYPE] {
+        assert!(mapper.find(|ty: &ResolveType| ty.event() == key).is_some(), "{key} is not registered");
+    }
+}
@@ -0,0 +1,24 @@
+use nitinol::{aggregate, Command, EntityId, Event, ToEntityId};
+
+#[derive(Command)]
+pub struct Open;
+
+#[derive(Event, serde::Serialize, serde::Deserialize)]
+#[persist(codec = "json")]
+pub struct Opened;
+
+pub struct Account;
+
+#[aggregate]
+impl Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+
+    #[handles]
+    async fn open(&self, _: Open) -> Result<Opened, ()> {
+        Ok(Opened)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use nitinol::{aggregate, Command, Event};
+
+#[derive(Command)]
+pub struct Open;
+
+#[derive(Event, serde::Serialize, serde::Deserialize)]
+#[persist(codec = "json")]
+pub struct Opened;
+
+pub struct Account;
+
+#[aggregate]
+impl Account {
+    #[handles]
+    fn open(&self, _: Open) -> Result<Opened, ()> {
+        Ok(Opened)
+    }
+
+    #[handles]
+    async fn reopen(&mut self, _: Open) -> Result<Opened, ()> {
+        Ok(Opened)
+    }
+
+    #[handles]
+    async fn close(&self, _: Open) -> Opened {
+        Opened
+    }
+
+    #[applies]
+    async fn opened(&self, _: Opened) {}
+}
+
+fn main() {}
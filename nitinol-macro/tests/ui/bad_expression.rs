@@ -0,0 +1,7 @@
+use nitinol_macro::Event;
+
+#[derive(Event)]
+#[persist(enc = "serde_json::to_vec(", dec = "serde_json::from_slice")]
+pub struct Registered;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use nitinol_macro::Event;
+
+#[derive(Event)]
+#[persist(codec = "json")]
+pub enum AccountEvent {
+    #[persist(key = "closed")]
+    Opened,
+    Closed,
+}
+
+fn main() {}
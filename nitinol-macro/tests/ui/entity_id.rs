@@ -0,0 +1,15 @@
+use nitinol::EntityId;
+
+#[derive(EntityId)]
+pub struct Pair(u64, u64);
+
+#[derive(EntityId)]
+pub enum Either {
+    Left(u64),
+}
+
+#[derive(EntityId)]
+#[entity(namespace = "order:v2")]
+pub struct OrderId(u64);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use nitinol::handles;
+
+pub struct Account;
+
+impl Account {
+    #[handles]
+    async fn open(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use nitinol_macro::Event;
+
+#[derive(Event)]
+#[persist(codec = "json", key = "user registered")]
+pub struct Registered;
+
+#[derive(Event)]
+#[persist(codec = "json", key = "")]
+pub struct Unregistered;
+
+fn main() {}
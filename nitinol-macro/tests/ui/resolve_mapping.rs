@@ -0,0 +1,19 @@
+use nitinol::projection::resolver::Project;
+use nitinol::{Event, ResolveMapping};
+
+#[derive(Event, serde::Serialize, serde::Deserialize)]
+#[persist(codec = "json")]
+pub struct Opened;
+
+#[derive(ResolveMapping)]
+#[resolve(Opened = Project)]
+pub struct Unprojected;
+
+#[derive(ResolveMapping)]
+#[resolve(Opened = Project, Opened = Project)]
+pub struct Duplicated;
+
+#[derive(ResolveMapping)]
+pub struct Empty;
+
+fn main() {}
@@ -0,0 +1,10 @@
+use nitinol_macro::Event;
+
+#[derive(Event)]
+#[persist(codec = "json")]
+pub union Bits {
+    a: u32,
+    b: f32,
+}
+
+fn main() {}
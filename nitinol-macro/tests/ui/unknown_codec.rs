@@ -0,0 +1,7 @@
+use nitinol_macro::Event;
+
+#[derive(Event)]
+#[persist(codec = "yaml")]
+pub struct Registered;
+
+fn main() {}
@@ -7,9 +7,18 @@ pub trait WithPersistence: 'static + Sync + Send
 where
     Self: Process,
 {
+    /// Write `event` at the current sequence, or stage it when the process runs in batch mode.
+    ///
+    /// Either way it goes to the journal of [`Context::id`], the id the process is registered and replayed under.
     async fn persist<E: Event>(&self, event: &E, ctx: &mut Context) {
+        if ctx.is_batching() {
+            if let Err(e) = ctx.stage(event) {
+                tracing::error!("on failure stage event caused reason `{e}`");
+            }
+            return;
+        }
         crate::global::get_global_writer()
-            .write(self.aggregate_id(), event, ctx.sequence())
+            .write(ctx.id().clone(), event, ctx.sequence(), ctx.command_id())
             .await;
     }
 }
@@ -1,6 +1,12 @@
+use std::error::Error;
+use async_trait::async_trait;
+use time::OffsetDateTime;
+use nitinol_core::command::CommandId;
 use nitinol_core::event::Event;
 use nitinol_core::identifier::EntityId;
+use nitinol_process::batch::{Journal, Staged};
 use nitinol_protocol::io::{WriteProtocol, Writer};
+use nitinol_protocol::Payload;
 
 #[derive(Debug, Clone)]
 pub struct EventWriter {
@@ -13,6 +19,11 @@ impl EventWriter {
         Self { writer: WriteProtocol::new(writer), retry: 3 }
     }
     
+    /// Write through `protocol`, to keep its configuration such as compression.
+    pub fn from_protocol(protocol: WriteProtocol) -> EventWriter {
+        Self { writer: protocol, retry: 3 }
+    }
+    
     pub fn set_retry(mut self, retry: i64) -> Self {
         self.retry = retry;
         self
@@ -20,10 +31,10 @@ impl EventWriter {
 }
 
 impl EventWriter {
-    pub(crate) async fn write<E: Event>(&self, id: EntityId, event: &E, seq: i64) {
+    pub(crate) async fn write<E: Event>(&self, id: EntityId, event: &E, seq: i64, command_id: Option<&CommandId>) {
         let mut retry = 0;
         loop {
-            match self.writer.write(id.clone(), event, seq).await {
+            match self.writer.write_with(id.clone(), event, seq, command_id).await {
                 Ok(()) => break,
                 Err(e) => {
                     tracing::error!("on failure persist caused reason `{e}`");
@@ -40,4 +51,38 @@ impl EventWriter {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Payload of an event staged by a process in batch mode, written to the journal of `id`.
+pub fn staged_payload(id: &EntityId, staged: Staged) -> Payload {
+    Payload::from_parts(id.to_string(), staged.sequence, staged.registry_key, staged.bytes, OffsetDateTime::now_utc())
+        .with_command_id(staged.command_id.as_ref())
+}
+
+/// Lets processes in batch mode flush their staged events through this writer,
+/// see `ProcessManager::with_batch`.
+#[async_trait]
+impl Journal for EventWriter {
+    async fn flush(&self, id: &EntityId, events: Vec<Staged>) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let payloads = events.into_iter()
+            .map(|staged| staged_payload(id, staged))
+            .collect::<Vec<_>>();
+        
+        let mut retry = 0;
+        loop {
+            match self.writer.write_batch(id.clone(), payloads.clone()).await {
+                Ok(()) => break Ok(()),
+                Err(e) => {
+                    tracing::error!("on failure persist batch caused reason `{e}`");
+                    
+                    retry += 1;
+                    
+                    if retry >= self.retry {
+                        tracing::error!("retry limit exceeded");
+                        break Err(Box::new(e));
+                    }
+                }
+            }
+        }
+    }
+}
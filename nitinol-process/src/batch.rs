@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use nitinol_core::command::CommandId;
+use nitinol_core::identifier::EntityId;
+
+use crate::errors::BatchWriteFailed;
+
+/// Event staged with [`Context::stage`](crate::Context::stage) while the process runs in batch mode.
+#[derive(Debug, Clone)]
+pub struct Staged {
+    pub sequence: i64,
+    pub registry_key: &'static str,
+    pub bytes: Vec<u8>,
+    pub command_id: Option<CommandId>,
+}
+
+/// Destination of the events staged during a batch.
+///
+/// See [`ProcessManager::with_batch`](crate::manager::ProcessManager::with_batch).
+#[async_trait]
+pub trait Journal: 'static + Sync + Send {
+    /// Write every event staged by `id` during one batch, in a single round trip if the storage allows it.
+    async fn flush(&self, id: &EntityId, events: Vec<Staged>) -> Result<(), Box<dyn Error + Sync + Send>>;
+}
+
+#[derive(Clone)]
+pub(crate) struct BatchSettings {
+    pub(crate) size: usize,
+    pub(crate) journal: Arc<dyn Journal>,
+}
+
+pub(crate) type Ack = Box<dyn FnOnce(Option<BatchWriteFailed>) + Sync + Send>;
+
+/// Events and replies held back until the end of the current batch.
+#[derive(Default)]
+pub(crate) struct Batch {
+    pub(crate) tasks: usize,
+    pub(crate) staged: Vec<Staged>,
+    pub(crate) acks: Vec<Ack>,
+}
+
+impl Batch {
+    pub(crate) fn take(&mut self) -> (Vec<Staged>, Vec<Ack>) {
+        self.tasks = 0;
+        (std::mem::take(&mut self.staged), std::mem::take(&mut self.acks))
+    }
+}
@@ -0,0 +1,50 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::message::Message;
+use crate::{Context, Process};
+
+/// Handler for `M` used in place of [`Receive<M>`](crate::task::Receive) while its [`Behavior`] is active.
+#[async_trait]
+pub trait BehaviorHandler<M: Message, T: Process>: 'static + Sync + Send {
+    async fn receive(&self, state: &mut T, message: M, ctx: &mut Context);
+}
+
+/// Set of message handlers a process switches to with [`Context::become_behavior`].
+///
+/// Messages without a handler in the active behavior still go to the [`Receive`](crate::task::Receive)
+/// implementation of the process.
+pub struct Behavior<T: Process> {
+    handlers: HashMap<TypeId, Box<dyn Any + Sync + Send>>,
+    _process: PhantomData<fn(T)>,
+}
+
+impl<T: Process> Behavior<T> {
+    pub fn new() -> Self {
+        Self { handlers: HashMap::new(), _process: PhantomData }
+    }
+
+    /// Handle `M` with `handler` while this behavior is active.
+    pub fn on<M: Message>(mut self, handler: impl BehaviorHandler<M, T>) -> Self {
+        let handler: Arc<dyn BehaviorHandler<M, T>> = Arc::new(handler);
+        self.handlers.insert(TypeId::of::<M>(), Box::new(handler));
+        self
+    }
+
+    pub(crate) fn handler<M: Message>(&self) -> Option<Arc<dyn BehaviorHandler<M, T>>> {
+        self.handlers
+            .get(&TypeId::of::<M>())?
+            .downcast_ref::<Arc<dyn BehaviorHandler<M, T>>>()
+            .cloned()
+    }
+}
+
+impl<T: Process> Default for Behavior<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,29 +1,87 @@
 mod status;
+mod dedup;
+mod views;
 
 pub use status::*;
 
+pub(crate) use dedup::*;
+pub(crate) use views::*;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{oneshot, Notify};
+
+use crate::batch::{Batch, Staged};
+use crate::behavior::Behavior;
+use crate::errors::{AlreadyExist, ProcessError, StageError};
+use crate::factory::Replayed;
+use crate::lifecycle::{self, Settings};
 use crate::registry::ProcessRegistry;
+use crate::supervisor::{ChildHandle, Link};
 use crate::{Process, Receptor};
 
-use nitinol_core::identifier::EntityId;
+use nitinol_core::command::CommandId;
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
 
 pub struct Context {
+    pub(crate) id: EntityId,
     pub(crate) sequence: i64,
     pub(crate) status: Status,
     pub(crate) registry: ProcessRegistry,
+    pub(crate) shutdown: Arc<Notify>,
+    pub(crate) myself: Option<Arc<dyn Link>>,
+    pub(crate) parent: Option<EntityId>,
+    pub(crate) children: HashMap<EntityId, ChildHandle>,
+    pub(crate) settings: Settings,
+    pub(crate) dedup: DedupWindow,
+    pub(crate) stashing: bool,
+    pub(crate) behavior: Option<Arc<dyn Any + Sync + Send>>,
+    pub(crate) views: Views,
+    pub(crate) batch: Option<Batch>,
 }
 
 impl Context {
     pub fn new(sequence: i64, registry: ProcessRegistry) -> Context {
-        Self { sequence, status: Status::new(true), registry }
+        Self {
+            id: EntityId::new(String::new()),
+            sequence,
+            status: Status::new(true),
+            registry,
+            shutdown: Arc::new(Notify::new()),
+            myself: None,
+            parent: None,
+            children: HashMap::new(),
+            settings: Settings::default(),
+            dedup: DedupWindow::default(),
+            stashing: false,
+            behavior: None,
+            views: Views::default(),
+            batch: None,
+        }
+    }
+
+    /// Set the identifier returned by [`Context::id`], which is empty otherwise.
+    pub fn with_id(mut self, id: impl ToEntityId) -> Context {
+        self.id = id.to_entity_id();
+        self
     }
 }
 
 impl Context {
+    /// Identifier under which this process is registered.
+    ///
+    /// For a child process this is the path `{parent}/{child}`.
+    pub fn id(&self) -> &EntityId {
+        &self.id
+    }
+
     pub fn sequence(&self) -> i64 {
         self.sequence
     }
-    
+
     pub fn status(&self) -> &Status {
         &self.status
     }
@@ -35,12 +93,194 @@ impl Context {
     pub async fn poison(&self) {
         self.status.poison().await;
     }
-    
+
     pub fn registry(&self) -> &ProcessRegistry {
         &self.registry
     }
-    
+
     pub async fn find<T: Process>(&self, id: &EntityId) -> Option<Receptor<T>> {
         self.registry.find::<T>(id).await.unwrap()
     }
+
+    /// Identifier of the process that spawned this one, if any.
+    pub fn parent(&self) -> Option<&EntityId> {
+        self.parent.as_ref()
+    }
+
+    /// Identifiers of the children that are still alive.
+    pub fn children(&self) -> impl Iterator<Item = &EntityId> {
+        self.children.keys()
+    }
+
+    /// Id of the handled command that produced the event at the current sequence, if it carried one.
+    ///
+    /// Lets [`EventApplicator`](crate::task::EventApplicator) implementations persist the id alongside the event.
+    pub fn command_id(&self) -> Option<&CommandId> {
+        self.dedup.at(self.sequence)
+    }
+
+    /// Add a command to the deduplication window, e.g. while restoring from a journal in [`Process::start`].
+    ///
+    /// Processes spawned with [`ProcessManager::spawn_replayed`](crate::manager::ProcessManager::spawn_replayed)
+    /// start with the commands of their journal already remembered.
+    ///
+    /// `bytes` is the serialized event the command produced at `sequence`.
+    pub fn remember(&mut self, id: CommandId, sequence: i64, bytes: Vec<u8>) {
+        self.dedup.record(id, Handled { sequence, bytes }, self.settings.dedup_window);
+    }
+
+    /// Set aside incoming commands, events and messages instead of applying them,
+    /// until [`Context::unstash_all`] is called.
+    ///
+    /// Supervision and system messages, and messages handled by the active [`Behavior`],
+    /// are still processed while stashing.
+    pub fn stash(&mut self) {
+        self.stashing = true;
+    }
+
+    /// Stop stashing. Stashed tasks are applied in arrival order before the rest of the mailbox.
+    pub fn unstash_all(&mut self) {
+        self.stashing = false;
+    }
+
+    pub fn is_stashing(&self) -> bool {
+        self.stashing
+    }
+
+    /// Handle messages with `behavior` until [`Context::unbecome`] or the next call.
+    ///
+    /// `T` must be the type of this process, otherwise the behavior is never used.
+    pub fn become_behavior<T: Process>(&mut self, behavior: Behavior<T>) {
+        self.behavior = Some(Arc::new(behavior));
+    }
+
+    /// Go back to the [`Receive`](crate::task::Receive) implementations of the process.
+    pub fn unbecome(&mut self) {
+        self.behavior = None;
+    }
+
+    /// Publish `view` to readers subscribed through [`Receptor::snapshot`], replacing the previous one.
+    ///
+    /// Readers observe the view without going through the mailbox.
+    pub fn publish<V: Clone + Sync + Send + 'static>(&self, view: V) {
+        self.views.publish(view);
+    }
+
+    /// Whether this process runs in batch mode, see [`ProcessManager::with_batch`](crate::manager::ProcessManager::with_batch).
+    pub fn is_batching(&self) -> bool {
+        self.batch.is_some()
+    }
+
+    /// Hold `event` back at the current sequence until the end of the batch,
+    /// when it is flushed to the journal together with the other staged events.
+    ///
+    /// Fails with [`StageError::NotBatching`] outside batch mode, check [`Context::is_batching`] first.
+    pub fn stage<E: Event>(&mut self, event: &E) -> Result<(), StageError> {
+        let command_id = self.command_id().cloned();
+        let Some(batch) = &mut self.batch else {
+            return Err(StageError::NotBatching);
+        };
+        batch.staged.push(Staged {
+            sequence: self.sequence,
+            registry_key: event.event_type(),
+            bytes: event.as_bytes()?,
+            command_id,
+        });
+        Ok(())
+    }
+
+    /// Watch `other`, calling [`Process::terminated`] on this process once it terminates.
+    pub fn watch<T: Process>(&self, other: &Receptor<T>) {
+        let Some(myself) = self.myself.clone() else {
+            return;
+        };
+        let id = other.id().clone();
+        let termination = other.watch();
+        tokio::spawn(async move {
+            myself.terminated(id, termination.await);
+        });
+    }
+
+    /// Spawn `child` as a child of this process.
+    ///
+    /// The child is registered under `{parent}/{child}` and inherits the settings of this process.
+    /// It is stopped when this process stops, and reports its failures and termination
+    /// to this process through [`Process::supervise`] and [`Process::terminated`].
+    pub async fn spawn_child<C: Process>(&mut self, child: C, start_seq: i64) -> Result<Receptor<C>, AlreadyExist> {
+        let id = EntityId::new(format!("{}/{}", self.id, child.aggregate_id()));
+        let parent = self.myself.clone().map(|link| (self.id.clone(), link));
+        let (refs, handle) = lifecycle::spawn(id.clone(), Replayed::new(child, start_seq), self.registry.clone(), parent, self.settings.clone()).await?;
+        self.children.insert(id, handle);
+        Ok(refs)
+    }
+}
+
+impl Context {
+    /// Event already produced by the command `id`, if it is still in the deduplication window.
+    ///
+    /// Applying the returned event again is then skipped.
+    pub(crate) fn duplicate<E: Event>(&mut self, id: &CommandId) -> Option<E> {
+        let handled = self.dedup.get(id)?;
+        match E::from_bytes(&handled.bytes) {
+            Ok(event) => {
+                let bytes = handled.bytes.clone();
+                self.dedup.redeliver(bytes, self.settings.dedup_window);
+                Some(event)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to restore event of command {id}, handling it again: {e}");
+                None
+            }
+        }
+    }
+
+    /// Send `reply`, or hold it back until the events of the current batch are written.
+    ///
+    /// A caller that stopped waiting is not a failure of the process, so a dropped channel is ignored.
+    pub(crate) fn reply<R: Sync + Send + 'static>(&mut self, oneshot: oneshot::Sender<Result<R, ProcessError>>, reply: R) {
+        let Some(batch) = &mut self.batch else {
+            let _ = oneshot.send(Ok(reply));
+            return;
+        };
+        batch.acks.push(Box::new(move |failed| {
+            let _ = oneshot.send(match failed {
+                Some(failed) => Err(failed.into()),
+                None => Ok(reply),
+            });
+        }));
+    }
+
+    pub(crate) fn record<E: Event>(&mut self, id: CommandId, event: &E) {
+        match event.as_bytes() {
+            Ok(bytes) => self.remember(id, self.sequence, bytes),
+            Err(e) => tracing::warn!("Failed to record event of command {id}: {e}"),
+        }
+    }
+
+    /// Record the command `id` as handled once `event` is applied.
+    pub(crate) fn await_apply<E: Event>(&mut self, id: CommandId, event: &E) {
+        match event.as_bytes() {
+            Ok(bytes) => self.dedup.await_apply(id, bytes, self.settings.dedup_window),
+            Err(e) => tracing::warn!("Failed to record event of command {id}: {e}"),
+        }
+    }
+
+    /// Complete the command that produced `event` before it is applied,
+    /// returning `false` if it was already applied and must be skipped.
+    pub(crate) fn claim<E: Event>(&mut self, event: &E) -> bool {
+        if !self.dedup.expects_events() {
+            return true;
+        }
+        let Ok(bytes) = event.as_bytes() else {
+            return true;
+        };
+        match self.dedup.claim(&bytes) {
+            Claim::Redelivered => false,
+            Claim::Command(id) => {
+                self.remember(id, self.sequence, bytes);
+                true
+            }
+            Claim::Nothing => true,
+        }
+    }
 }
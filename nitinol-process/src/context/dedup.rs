@@ -0,0 +1,94 @@
+use std::collections::{HashMap, VecDeque};
+
+use nitinol_core::command::CommandId;
+
+/// Event produced by a command that carried a [`CommandId`].
+pub(crate) struct Handled {
+    pub(crate) sequence: i64,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// What applying an event with given bytes completes.
+pub(crate) enum Claim {
+    /// The event of a command handled by [`Receptor::handle`](crate::Receptor::handle), which becomes handled.
+    Command(CommandId),
+    /// An event already applied, returned again for a redelivered command.
+    Redelivered,
+    Nothing,
+}
+
+/// Recently handled commands of a process, the oldest evicted first.
+///
+/// A command sent with [`Receptor::handle`](crate::Receptor::handle) is only handled once its event
+/// is applied. Until then it waits, matched to the event by its serialized bytes.
+#[derive(Default)]
+pub(crate) struct DedupWindow {
+    order: VecDeque<CommandId>,
+    handled: HashMap<CommandId, Handled>,
+    awaiting: VecDeque<(CommandId, Vec<u8>)>,
+    redelivered: VecDeque<Vec<u8>>,
+}
+
+impl DedupWindow {
+    pub(crate) fn get(&self, id: &CommandId) -> Option<&Handled> {
+        self.handled.get(id)
+    }
+
+    pub(crate) fn record(&mut self, id: CommandId, handled: Handled, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        if self.handled.insert(id.clone(), handled).is_none() {
+            self.order.push_back(id);
+        }
+        while self.order.len() > capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.handled.remove(&evicted);
+            }
+        }
+    }
+
+    /// Wait for the event `bytes` produced by the command `id` to be applied.
+    pub(crate) fn await_apply(&mut self, id: CommandId, bytes: Vec<u8>, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        self.awaiting.retain(|(awaiting, _)| *awaiting != id);
+        self.awaiting.push_back((id, bytes));
+        if self.awaiting.len() > capacity {
+            self.awaiting.pop_front();
+        }
+    }
+
+    /// Expect the already applied event `bytes`, returned for a redelivered command, to be applied again.
+    pub(crate) fn redeliver(&mut self, bytes: Vec<u8>, capacity: usize) {
+        self.redelivered.push_back(bytes);
+        if self.redelivered.len() > capacity {
+            self.redelivered.pop_front();
+        }
+    }
+
+    /// Whether an applied event may complete a command, and has to be serialized to find out.
+    pub(crate) fn expects_events(&self) -> bool {
+        !self.awaiting.is_empty() || !self.redelivered.is_empty()
+    }
+
+    pub(crate) fn claim(&mut self, bytes: &[u8]) -> Claim {
+        if let Some(index) = self.redelivered.iter().position(|redelivered| redelivered == bytes) {
+            self.redelivered.remove(index);
+            return Claim::Redelivered;
+        }
+        match self.awaiting.iter().position(|(_, awaiting)| awaiting == bytes) {
+            Some(index) => Claim::Command(self.awaiting.remove(index).expect("found above").0),
+            None => Claim::Nothing,
+        }
+    }
+
+    /// Id of the most recently handled command whose event is at `sequence`.
+    pub(crate) fn at(&self, sequence: i64) -> Option<&CommandId> {
+        self.order
+            .iter()
+            .rev()
+            .find(|id| self.handled[*id].sequence == sequence)
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+#[derive(Debug)]
 pub struct Status(Arc<RwLock<bool>>);
 
 impl Clone for Status {
@@ -0,0 +1,37 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+/// Views published by a process, one watch channel per view type.
+#[derive(Clone, Default)]
+pub(crate) struct Views(Arc<Mutex<HashMap<TypeId, Box<dyn Any + Sync + Send>>>>);
+
+impl Views {
+    pub(crate) fn publish<V: Clone + Sync + Send + 'static>(&self, view: V) {
+        let mut views = self.0.lock().expect("views lock poisoned");
+        match views.get(&TypeId::of::<V>()).and_then(|sender| sender.downcast_ref::<watch::Sender<V>>()) {
+            Some(sender) => {
+                sender.send_replace(view);
+            }
+            None => {
+                let (sender, _) = watch::channel(view);
+                views.insert(TypeId::of::<V>(), Box::new(sender));
+            }
+        }
+    }
+
+    pub(crate) fn subscribe<V: Clone + Sync + Send + 'static>(&self) -> Option<watch::Receiver<V>> {
+        let views = self.0.lock().expect("views lock poisoned");
+        views.get(&TypeId::of::<V>())?
+            .downcast_ref::<watch::Sender<V>>()
+            .map(watch::Sender::subscribe)
+    }
+}
+
+impl std::fmt::Debug for Views {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Views").finish_non_exhaustive()
+    }
+}
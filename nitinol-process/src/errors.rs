@@ -1,3 +1,6 @@
+use std::error::Error;
+
+use nitinol_core::errors::SerializeError;
 use nitinol_core::identifier::EntityId;
 
 #[derive(Debug, thiserror::Error)]
@@ -17,3 +20,84 @@ pub struct AlreadyExist(pub EntityId);
 #[derive(Debug, thiserror::Error)]
 #[error("Not found {0} in registry")]
 pub struct NotFound(pub EntityId);
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Process panicked while applying a task: {0}")]
+pub struct Panicked(pub String);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Process {0} has been poisoned")]
+pub struct Poisoned(pub EntityId);
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Task rejected by interceptor: {0}")]
+pub struct Intercepted(pub String);
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Failed to receive {message}: {reason}")]
+pub struct ReceiveFailed {
+    pub message: &'static str,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Failure of child {child} escalated: {reason}")]
+pub struct Escalated {
+    pub child: EntityId,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Failed to write batched events: {0}")]
+pub struct BatchWriteFailed(pub String);
+
+/// Error returned by [`Context::stage`](crate::Context::stage).
+#[derive(Debug, thiserror::Error)]
+pub enum StageError {
+    #[error("Process is not running in batch mode")]
+    NotBatching,
+    #[error(transparent)]
+    Serialize(#[from] SerializeError),
+}
+
+/// Error returned to callers of [`Receptor`](crate::Receptor).
+#[derive(Debug, thiserror::Error)]
+pub enum ProcessError {
+    #[error(transparent)]
+    ChannelDropped(#[from] ChannelDropped),
+    #[error(transparent)]
+    Panicked(#[from] Panicked),
+    #[error(transparent)]
+    Poisoned(#[from] Poisoned),
+    #[error(transparent)]
+    Intercepted(#[from] Intercepted),
+    #[error(transparent)]
+    ReceiveFailed(#[from] ReceiveFailed),
+    #[error(transparent)]
+    Escalated(#[from] Escalated),
+    #[error(transparent)]
+    BatchWriteFailed(#[from] BatchWriteFailed),
+}
+
+/// Error returned by [`ProcessManager::route`](crate::manager::ProcessManager::route) and [`Router`](crate::router::Router).
+#[derive(Debug, thiserror::Error)]
+pub enum RouteError {
+    #[error("No factory registered for {0}")]
+    NoFactory(&'static str),
+    #[error("Failed to create {id}: {source}")]
+    Factory {
+        id: EntityId,
+        #[source]
+        source: Box<dyn Error + Sync + Send>,
+    },
+    #[error("Pool {0} has no routees")]
+    NoRoutee(EntityId),
+    #[error("Pool {0} routes by consistent hash and needs a key")]
+    KeyRequired(EntityId),
+    #[error(transparent)]
+    AlreadyExist(#[from] AlreadyExist),
+    #[error(transparent)]
+    InvalidCast(#[from] InvalidCast),
+    #[error(transparent)]
+    Process(#[from] ProcessError),
+}
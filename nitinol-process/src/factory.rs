@@ -0,0 +1,61 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use nitinol_core::command::CommandId;
+use nitinol_core::identifier::EntityId;
+
+use crate::Process;
+
+/// Builds the entity of a process that is not running yet,
+/// see [`ProcessManager::route`](crate::manager::ProcessManager::route).
+///
+/// Any `Fn(&EntityId) -> T` is a factory starting the entity at sequence `0`.
+#[async_trait]
+pub trait Factory<T: Process>: 'static + Sync + Send {
+    /// Returns the entity identified by `id` together with the sequence it starts from.
+    async fn create(&self, id: &EntityId) -> Result<Replayed<T>, Box<dyn Error + Sync + Send>>;
+}
+
+#[async_trait]
+impl<T: Process, F> Factory<T> for F
+where
+    F: Fn(&EntityId) -> T + 'static + Sync + Send,
+{
+    async fn create(&self, id: &EntityId) -> Result<Replayed<T>, Box<dyn Error + Sync + Send>> {
+        Ok(Replayed::new(self(id), 0))
+    }
+}
+
+/// Entity restored from its journal, ready to be spawned with
+/// [`ProcessManager::spawn_replayed`](crate::manager::ProcessManager::spawn_replayed).
+pub struct Replayed<T> {
+    pub entity: T,
+    pub sequence: i64,
+    /// Commands that produced the replayed events, which seed the deduplication window of the process.
+    pub handled: Vec<HandledCommand>,
+}
+
+impl<T> Replayed<T> {
+    pub fn new(entity: T, sequence: i64) -> Replayed<T> {
+        Self { entity, sequence, handled: Vec::new() }
+    }
+
+    pub fn with_handled(mut self, handled: Vec<HandledCommand>) -> Replayed<T> {
+        self.handled = handled;
+        self
+    }
+}
+
+impl<T> From<(T, i64)> for Replayed<T> {
+    fn from((entity, sequence): (T, i64)) -> Self {
+        Replayed::new(entity, sequence)
+    }
+}
+
+/// Command carrying a [`CommandId`] that produced the serialized event `bytes` at `sequence`.
+#[derive(Debug, Clone)]
+pub struct HandledCommand {
+    pub id: CommandId,
+    pub sequence: i64,
+    pub bytes: Vec<u8>,
+}
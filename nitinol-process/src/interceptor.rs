@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::errors::{Intercepted, ProcessError};
+use crate::metrics::TaskKind;
+use crate::task::Outcome;
+use crate::Context;
+
+/// Description of a command, event or message about to be applied, passed to an [`Interceptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskInfo {
+    pub kind: TaskKind,
+    /// Type name of the command, event or message.
+    pub name: &'static str,
+}
+
+/// Hook wrapping every command, event, entrusted command and message applied by a process.
+///
+/// Installed through [`ProcessManager::with_interceptor`](crate::manager::ProcessManager::with_interceptor)
+/// and run in installation order. Supervision and death watch notifications are not intercepted.
+#[allow(unused_variables)]
+#[async_trait]
+pub trait Interceptor: 'static + Sync + Send {
+    /// Called before the task is applied.
+    ///
+    /// Returning an error skips the task and every following interceptor,
+    /// and the caller receives [`ProcessError::Intercepted`].
+    async fn before(&self, task: &TaskInfo, ctx: &Context) -> Result<(), Intercepted> {
+        Ok(())
+    }
+
+    /// Called after the task has been applied, including when it failed or panicked.
+    async fn after(&self, task: &TaskInfo, ctx: &Context, result: &Result<Outcome, ProcessError>, elapsed: Duration) {}
+}
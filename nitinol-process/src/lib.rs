@@ -7,6 +7,14 @@ mod receptor;
 mod process;
 pub mod manager;
 pub mod message;
+pub mod metrics;
+pub mod supervisor;
+pub mod interceptor;
+pub mod mailbox;
+pub mod behavior;
+pub mod batch;
+pub mod factory;
+pub mod router;
 
 pub use self::context::*;
 pub use self::process::*;
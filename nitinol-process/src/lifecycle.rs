@@ -1,10 +1,79 @@
-use std::time::Duration;
-use nitinol_core::identifier::ToEntityId;
-use crate::task::TaskApplier;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::Instrument;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use crate::task::{isolate, Outcome, TaskApplier};
 use crate::{Process, Context};
-use crate::errors::AlreadyExist;
+use crate::batch::{BatchSettings, Journal};
+use crate::factory::Replayed;
+use crate::errors::{AlreadyExist, BatchWriteFailed, Poisoned, ProcessError};
+use crate::mailbox;
 use crate::receptor::Receptor;
 use crate::registry::ProcessRegistry;
+use crate::interceptor::{Interceptor, TaskInfo};
+use crate::metrics::{Disabled, Metrics, TaskKind};
+use crate::supervisor::{ChildHandle, Directive, FailurePolicy, Link, ProcessLink};
+
+/// Reason a process stopped, reported to watchers.
+///
+/// See [`Receptor::watch`] and [`Context::watch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Termination {
+    /// Stopped normally, by its parent or because every [`Receptor`] was dropped.
+    Stopped,
+    /// Stopped after [`Context::poison`] was called.
+    Poisoned,
+    /// No task arrived within the configured timeout.
+    Timeout,
+    /// A task panicked.
+    Panicked(String),
+    /// A task failed.
+    Failed(String),
+}
+
+impl From<&ProcessError> for Termination {
+    fn from(error: &ProcessError) -> Self {
+        match error {
+            ProcessError::Panicked(panicked) => Termination::Panicked(panicked.0.clone()),
+            error => Termination::Failed(error.to_string()),
+        }
+    }
+}
+
+/// State of a running process, returned by [`Receptor::info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub id: EntityId,
+    pub sequence: i64,
+    /// Identifiers of the children that are still alive.
+    pub children: Vec<EntityId>,
+}
+
+/// Settings applied to a process and inherited by its children.
+#[derive(Clone)]
+pub(crate) struct Settings {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) policy: FailurePolicy,
+    pub(crate) metrics: Arc<dyn Metrics>,
+    pub(crate) interceptors: Vec<Arc<dyn Interceptor>>,
+    pub(crate) dedup_window: usize,
+    pub(crate) batch: Option<BatchSettings>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            policy: FailurePolicy::default(),
+            metrics: Arc::new(Disabled),
+            interceptors: Vec::new(),
+            dedup_window: 256,
+            batch: None,
+        }
+    }
+}
 
 pub async fn run<T: Process>(
     id: impl ToEntityId,
@@ -13,82 +82,174 @@ pub async fn run<T: Process>(
     registry: ProcessRegistry,
     timeout: Option<Duration>
 ) -> Result<Receptor<T>, AlreadyExist> {
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Box<dyn TaskApplier<T>>>();
+    let settings = Settings { timeout, ..Settings::default() };
+    let (refs, _) = spawn(id.to_entity_id(), Replayed::new(entity, start_seq), registry, None, settings).await?;
+    Ok(refs)
+}
+
+pub(crate) async fn spawn<T: Process>(
+    entity_id: EntityId,
+    replayed: Replayed<T>,
+    registry: ProcessRegistry,
+    parent: Option<(EntityId, Arc<dyn Link>)>,
+    settings: Settings,
+) -> Result<(Receptor<T>, ChildHandle), AlreadyExist> {
+    let (tx, mut rx) = mailbox::channel::<T>();
+    let (terminated, watcher) = watch::channel(None);
+
+    let Replayed { entity, sequence, handled } = replayed;
+    let mut context = Context::new(sequence, registry.clone()).with_id(entity_id.clone());
+    context.myself = Some(Arc::new(ProcessLink { channel: tx.downgrade_system() }));
+    context.batch = settings.batch.as_ref().map(|_| Default::default());
+    context.settings = settings;
+    for command in handled {
+        context.remember(command.id, command.sequence, command.bytes);
+    }
+
+    let refs = Receptor {
+        id: entity_id.clone(),
+        channel: tx,
+        terminated: watcher.clone(),
+        status: context.status.clone(),
+        views: context.views.clone(),
+    };
+    let handle = ChildHandle { shutdown: Arc::clone(&context.shutdown), terminated: watcher };
+
+    let supervisor = parent.map(|(parent_id, link)| {
+        context.parent = Some(parent_id);
+        link
+    });
 
-    let entity_id = id.to_entity_id();
-    let refs = Receptor { channel: tx };
-    
-    let context = Context::new(start_seq, registry.clone());
-    
     #[cfg(tokio_unstable)]
     let named = entity_id.clone();
-    
-    registry.register(entity_id.clone(), refs.clone()).await?;
-    
+
+    let registered = registry.register(entity_id.clone(), refs.clone()).await?;
+
     let process = async move {
         let id = entity_id;
         let registry = registry;
         let mut state = entity;
         let mut context = context;
-        
-        state.start(&mut context).await;
-        
-        state = if let Some(timeout) = timeout {
+        let shutdown = Arc::clone(&context.shutdown);
+        let Settings { timeout, policy, metrics, interceptors, batch, .. } = context.settings.clone();
+        let mut stash = VecDeque::new();
+        let spawned_at = Instant::now();
+
+        let started = isolate(state.start(&mut context)).await;
+
+        let reason = if let Err(panicked) = started {
+            tracing::error!("{panicked}");
+            Termination::Panicked(panicked.0)
+        } else {
             loop {
-                tokio::pin! {
-                    let timeout = tokio::time::sleep(timeout);
+                if !context.is_active().await {
+                    tracing::info!("Process poisoned.");
+                    break Termination::Poisoned;
                 }
-                tokio::select! {
-                    Some(task) = rx.recv() => {
-                        if let Err(e) = task.apply(&mut state, &mut context).await {
-                            tracing::error!("{e}");
-                            break state;
+                if let (Some(settings), Some(pending)) = (&batch, &context.batch) {
+                    let idle = rx.is_empty() && (context.stashing || stash.is_empty());
+                    if pending.tasks >= settings.size || (pending.tasks > 0 && idle) {
+                        // The state and the sequence already include the events the journal did not record.
+                        if let Err(failed) = flush(&mut context, settings.journal.as_ref()).await {
+                            break Termination::Failed(failed.to_string());
                         }
                     }
-                    _ = &mut timeout => {
-                        tracing::info!("Process timeout.");
-                        break state;
-                    }
                 }
-            }
-        } else {
-            loop {
-                match rx.recv().await {
-                    Some(task) => {
-                        if let Err(e) = task.apply(&mut state, &mut context).await {
-                            tracing::error!("{e}");
-                            break state
+                let stashed = if context.stashing { None } else { stash.pop_front() };
+                let task = match stashed {
+                    Some(task) => task,
+                    None => tokio::select! {
+                        biased;
+                        _ = shutdown.notified() => {
+                            break Termination::Stopped;
+                        }
+                        task = rx.recv() => {
+                            let Some(task) = task else {
+                                break Termination::Stopped;
+                            };
+                            metrics.mailbox_depth(&id, rx.len());
+                            task
                         }
+                        _ = idle(timeout) => {
+                            tracing::info!("Process timeout.");
+                            break Termination::Timeout;
+                        }
+                    },
+                };
+                let kind = task.kind();
+                if context.stashing && kind != TaskKind::System && !task.handled_by_behavior(&context) {
+                    stash.push_back(task);
+                    continue;
+                }
+                if let Some(pending) = &mut context.batch {
+                    pending.tasks += 1;
+                }
+                let span = tracing::info_span!(
+                    "task",
+                    id = %id,
+                    sequence = context.sequence,
+                    kind = %kind,
+                    task = task.name(),
+                );
+                let began_at = Instant::now();
+                let applied = execute(task, &mut state, &mut context, &interceptors).instrument(span).await;
+                metrics.task_processed(&id, kind, began_at.elapsed());
+                let e = match applied {
+                    Ok(Outcome::Accepted) => continue,
+                    Ok(Outcome::Rejected) | Err(ProcessError::Intercepted(_)) => {
+                        metrics.rejected(&id, kind);
+                        continue;
                     }
-                    None => break state
+                    Err(e) => e,
+                };
+                tracing::error!("{e}");
+                let resume = match &supervisor {
+                    // The parent may be stopping us while we wait for its decision.
+                    Some(supervisor) => tokio::select! {
+                        directive = supervisor.supervise(id.clone(), e.to_string()) => directive == Directive::Resume,
+                        _ = shutdown.notified() => false,
+                    },
+                    None => policy == FailurePolicy::Continue,
+                };
+                if !resume {
+                    break Termination::from(&e);
                 }
             }
         };
-        
-        
-        // while let Some(rx) = rx.recv().await {
-        //     entity = match rx.apply(entity, &mut context).await {
-        //         Ok(entity) => entity,
-        //         Err(e) => {
-        //             tracing::error!("{e}");
-        //             break;
-        //         }
-        //     };
-        //     
-        //     if !context.is_active().await {
-        //         tracing::warn!("lifecycle ended.");
-        //         break;
-        //     }
-        // }
-        
-        state.stop(&mut context).await;
-        
-        if let Err(e) = registry.deregister(&id).await {
+
+        if let Some(settings) = &batch {
+            let _ = flush(&mut context, settings.journal.as_ref()).await;
+        }
+
+        rx.close();
+        while let Some(task) = stash.pop_front().or_else(|| rx.try_recv()) {
+            metrics.dropped(&id, task.kind());
+            if reason == Termination::Poisoned {
+                task.reject(Poisoned(id.clone()).into());
+            }
+        }
+
+        for (_, child) in context.children.drain() {
+            child.stop().await;
+        }
+
+        if let Err(panicked) = isolate(state.stop(&mut context)).await {
+            tracing::error!("{panicked}");
+        }
+
+        if let Err(e) = registry.deregister(&id, &registered).await {
             tracing::error!("{e}");
         }
-        
+
+        metrics.lifetime(&id, spawned_at.elapsed(), &reason);
+
+        if let Some(supervisor) = supervisor {
+            supervisor.terminated(id, reason.clone());
+        }
+
+        let _ = terminated.send(Some(reason));
     };
-    
+
     #[cfg(tokio_unstable)]
     {
         let _ = tokio::task::Builder::new()
@@ -96,9 +257,77 @@ pub async fn run<T: Process>(
             .spawn(process)
             .expect("unexpected error occurred from tokio-runtime.");
     }
-    
+
     #[cfg(not(tokio_unstable))]
     tokio::spawn(process);
-    
-    Ok(refs)
+
+    Ok((refs, handle))
+}
+
+/// Apply `task`, running it through `interceptors` unless it is a system task.
+async fn execute<T: Process>(
+    task: Box<dyn TaskApplier<T>>,
+    state: &mut T,
+    context: &mut Context,
+    interceptors: &[Arc<dyn Interceptor>],
+) -> Result<Outcome, ProcessError> {
+    let kind = task.kind();
+    if kind == TaskKind::System || interceptors.is_empty() {
+        return task.apply(state, context).await;
+    }
+
+    let info = TaskInfo { kind, name: task.name() };
+    for interceptor in interceptors {
+        match isolate(interceptor.before(&info, context)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(intercepted)) => {
+                tracing::debug!("{intercepted}");
+                task.reject(intercepted.clone().into());
+                return Err(intercepted.into());
+            }
+            Err(panicked) => {
+                task.reject(panicked.clone().into());
+                return Err(panicked.into());
+            }
+        }
+    }
+
+    let began_at = Instant::now();
+    let applied = task.apply(state, context).await;
+    let elapsed = began_at.elapsed();
+    for interceptor in interceptors {
+        if let Err(panicked) = isolate(interceptor.after(&info, context, &applied, elapsed)).await {
+            tracing::error!("{panicked}");
+        }
+    }
+    applied
+}
+
+/// Write the events staged during the current batch, then release the replies held back meanwhile.
+async fn flush(context: &mut Context, journal: &dyn Journal) -> Result<(), BatchWriteFailed> {
+    let Some(batch) = &mut context.batch else {
+        return Ok(());
+    };
+    let (staged, acks) = batch.take();
+    let failed = if staged.is_empty() {
+        None
+    } else {
+        journal.flush(&context.id, staged).await
+            .err()
+            .map(|e| BatchWriteFailed(e.to_string()))
+    };
+    if let Some(failed) = &failed {
+        tracing::error!("{failed}");
+    }
+    for ack in acks {
+        ack(failed.clone());
+    }
+    failed.map_or(Ok(()), Err)
+}
+
+async fn idle(timeout: Option<Duration>) {
+    match timeout {
+        Some(timeout) => tokio::time::sleep(timeout).await,
+        None => std::future::pending().await,
+    }
 }
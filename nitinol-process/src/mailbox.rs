@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender, WeakUnboundedSender};
+
+use crate::task::TaskApplier;
+use crate::Process;
+
+/// Lane a message sent through [`Receptor::send_with`](crate::Receptor::send_with) is queued in.
+///
+/// High priority messages are processed before any normal one already waiting in the mailbox.
+/// System messages such as stop requests and supervision notifications always come first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+}
+
+pub(crate) fn channel<T: Process>() -> (Sender<T>, Mailbox<T>) {
+    let (system_tx, system_rx) = mpsc::unbounded_channel();
+    let (high_tx, high_rx) = mpsc::unbounded_channel();
+    let (normal_tx, normal_rx) = mpsc::unbounded_channel();
+    let depth = Arc::new(AtomicUsize::new(0));
+    let sender = Sender { system: system_tx, high: high_tx, normal: normal_tx, depth: Arc::clone(&depth) };
+    let mailbox = Mailbox { system: system_rx, high: high_rx, normal: normal_rx, depth };
+    (sender, mailbox)
+}
+
+#[derive(Debug)]
+pub(crate) struct Sender<T: Process> {
+    system: UnboundedSender<Box<dyn TaskApplier<T>>>,
+    high: UnboundedSender<Box<dyn TaskApplier<T>>>,
+    normal: UnboundedSender<Box<dyn TaskApplier<T>>>,
+    depth: Arc<AtomicUsize>,
+}
+
+impl<T: Process> Sender<T> {
+    pub(crate) fn send(&self, task: Box<dyn TaskApplier<T>>, priority: Priority) -> Result<(), Box<dyn TaskApplier<T>>> {
+        let lane = match priority {
+            Priority::High => &self.high,
+            Priority::Normal => &self.normal,
+        };
+        // Counted before sending so that the receiver never decrements first.
+        self.depth.fetch_add(1, Ordering::Relaxed);
+        lane.send(task).map_err(|e| {
+            self.depth.fetch_sub(1, Ordering::Relaxed);
+            e.0
+        })
+    }
+
+    pub(crate) fn send_system(&self, task: Box<dyn TaskApplier<T>>) -> Result<(), Box<dyn TaskApplier<T>>> {
+        self.system.send(task).map_err(|e| e.0)
+    }
+
+    /// Number of high and normal priority tasks waiting in the mailbox.
+    pub(crate) fn len(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// Sender of the system lane that does not keep the process alive.
+    pub(crate) fn downgrade_system(&self) -> WeakUnboundedSender<Box<dyn TaskApplier<T>>> {
+        self.system.downgrade()
+    }
+}
+
+impl<T: Process> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            system: self.system.clone(),
+            high: self.high.clone(),
+            normal: self.normal.clone(),
+            depth: Arc::clone(&self.depth),
+        }
+    }
+}
+
+pub(crate) struct Mailbox<T: Process> {
+    system: UnboundedReceiver<Box<dyn TaskApplier<T>>>,
+    high: UnboundedReceiver<Box<dyn TaskApplier<T>>>,
+    normal: UnboundedReceiver<Box<dyn TaskApplier<T>>>,
+    depth: Arc<AtomicUsize>,
+}
+
+impl<T: Process> Mailbox<T> {
+    /// Receive the next task, taking system, high and normal lanes in that order.
+    ///
+    /// Returns `None` once every lane is closed and empty.
+    pub(crate) async fn recv(&mut self) -> Option<Box<dyn TaskApplier<T>>> {
+        tokio::select! {
+            biased;
+            Some(task) = self.system.recv() => Some(task),
+            Some(task) = self.high.recv() => Some(self.dequeued(task)),
+            Some(task) = self.normal.recv() => Some(self.dequeued(task)),
+            else => None,
+        }
+    }
+
+    pub(crate) fn try_recv(&mut self) -> Option<Box<dyn TaskApplier<T>>> {
+        if let Ok(task) = self.system.try_recv() {
+            return Some(task);
+        }
+        let task = self.high.try_recv().or_else(|_| self.normal.try_recv()).ok()?;
+        Some(self.dequeued(task))
+    }
+
+    fn dequeued(&self, task: Box<dyn TaskApplier<T>>) -> Box<dyn TaskApplier<T>> {
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+        task
+    }
+
+    /// Number of high and normal priority tasks waiting, the same as [`Sender::len`].
+    pub(crate) fn len(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// Whether no task is waiting in any lane, system lane included.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.system.is_empty() && self.high.is_empty() && self.normal.is_empty()
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.system.close();
+        self.high.close();
+        self.normal.close();
+    }
+}
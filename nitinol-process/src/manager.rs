@@ -1,20 +1,138 @@
-use nitinol_core::identifier::ToEntityId;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
 
-use crate::errors::{AlreadyExist, InvalidCast};
+use nitinol_core::command::Command;
+use nitinol_core::identifier::{EntityId, ToEntityId, TypedId};
+
+use crate::batch::{BatchSettings, Journal};
+use crate::errors::{AlreadyExist, ChannelDropped, InvalidCast, RouteError};
+use crate::factory::{Factory, Replayed};
+use crate::interceptor::Interceptor;
 use crate::registry::ProcessRegistry;
+use crate::lifecycle::Settings;
+use crate::metrics::Metrics;
+use crate::router::{Router, Strategy};
+use crate::supervisor::FailurePolicy;
+use crate::task::CommandHandler;
 use crate::{lifecycle, Process, Receptor};
 
 #[derive(Clone, Default)]
 pub struct ProcessManager {
-    registry: ProcessRegistry
+    registry: ProcessRegistry,
+    settings: Settings,
+    factories: HashMap<TypeId, Arc<dyn Any + Sync + Send>>,
 }
 
 impl ProcessManager {
+    /// Set what spawned processes do when a task fails or panics. Defaults to [`FailurePolicy::Stop`].
+    pub fn with_policy(mut self, policy: FailurePolicy) -> Self {
+        self.settings.policy = policy;
+        self
+    }
+
+    /// Report mailbox and task measurements of spawned processes to `metrics`.
+    pub fn with_metrics(mut self, metrics: impl Metrics) -> Self {
+        self.settings.metrics = Arc::new(metrics);
+        self
+    }
+
+    /// Run every command, event and message applied by spawned processes through `interceptor`.
+    ///
+    /// Interceptors run in the order they were added.
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        self.settings.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Number of handled [`CommandId`](nitinol_core::command::CommandId)s each process remembers
+    /// to answer redelivered commands. Defaults to 256, `0` disables deduplication.
+    pub fn with_dedup_window(mut self, capacity: usize) -> Self {
+        self.settings.dedup_window = capacity;
+        self
+    }
+
+    /// Run spawned processes in batch mode.
+    ///
+    /// Up to `size` queued tasks are applied back to back, and the events they stage with
+    /// [`Context::stage`](crate::Context::stage) are written to `journal` at once.
+    /// Replies to [`Receptor::handle`] and [`Receptor::apply`] are held back until that write completes,
+    /// and fail with [`BatchWriteFailed`](crate::errors::BatchWriteFailed) if it does not.
+    /// The process then stops with [`Termination::Failed`](crate::lifecycle::Termination::Failed),
+    /// since its state already includes the events the journal did not record.
+    pub fn with_batch(mut self, size: usize, journal: impl Journal) -> Self {
+        self.settings.batch = Some(BatchSettings { size: size.max(1), journal: Arc::new(journal) });
+        self
+    }
+
+    /// Create processes of type `T` with `factory` when a command is routed to one that is not running.
+    pub fn with_factory<T: Process>(mut self, factory: impl Factory<T>) -> Self {
+        let factory: Arc<dyn Factory<T>> = Arc::new(factory);
+        self.factories.insert(TypeId::of::<T>(), Arc::new(factory));
+        self
+    }
+
     pub async fn spawn<T: Process>(&self, entity: T, start_seq: i64) -> Result<Receptor<T>, AlreadyExist> {
-        lifecycle::run(entity.aggregate_id(), entity, start_seq, self.registry.clone(), None).await
+        self.spawn_replayed(Replayed::new(entity, start_seq)).await
+    }
+
+    /// Spawn an entity restored from its journal, remembering the commands it already handled
+    /// so that they are not applied again when redelivered.
+    pub async fn spawn_replayed<T: Process>(&self, replayed: Replayed<T>) -> Result<Receptor<T>, AlreadyExist> {
+        self.spawn_as(replayed.entity.aggregate_id(), replayed).await
+    }
+
+    pub(crate) async fn spawn_as<T: Process>(&self, id: EntityId, replayed: Replayed<T>) -> Result<Receptor<T>, AlreadyExist> {
+        let (refs, _) = lifecycle::spawn(id, replayed, self.registry.clone(), None, self.settings.clone()).await?;
+        Ok(refs)
+    }
+
+    /// Spawn `size` processes created by `factory` behind a [`Router`] identified by `id`.
+    pub async fn pool<T: Process>(&self, id: impl ToEntityId, size: usize, strategy: Strategy, factory: impl Factory<T>) -> Result<Router<T>, RouteError> {
+        Router::new(id.to_entity_id(), size, strategy, self.clone(), Arc::new(factory)).await
     }
 
     pub async fn find<T: Process>(&self, id: impl ToEntityId) -> Result<Option<Receptor<T>>, InvalidCast> {
         self.registry.find::<T>(&id.to_entity_id()).await
     }
+
+    /// Ids of the running processes whose id is an `I`.
+    ///
+    /// An id is listed whenever its text parses as an `I`, however it was built. The paths of child processes
+    /// and routees, `{parent}/{child}`, are listed too if they still parse, as they do for an `I` holding a `String`.
+    pub async fn ids<I: TypedId>(&self) -> Vec<I> {
+        self.registry.ids::<I>().await
+    }
+
+    /// Find the process `id`, spawning it through the factory registered for `T` if it is not running.
+    pub async fn locate<T: Process>(&self, id: impl ToEntityId) -> Result<Receptor<T>, RouteError> {
+        let id = id.to_entity_id();
+        if let Some(refs) = self.registry.find::<T>(&id).await? {
+            return Ok(refs);
+        }
+
+        let factory = self.factories
+            .get(&TypeId::of::<T>())
+            .and_then(|factory| factory.downcast_ref::<Arc<dyn Factory<T>>>())
+            .ok_or(RouteError::NoFactory(std::any::type_name::<T>()))?;
+        let replayed = factory.create(&id).await
+            .map_err(|source| RouteError::Factory { id: id.clone(), source })?;
+
+        match self.spawn_as(id.clone(), replayed).await {
+            Ok(refs) => Ok(refs),
+            // Someone else spawned it while the entity was being created.
+            Err(AlreadyExist(_)) => self.registry
+                .find::<T>(&id).await?
+                .ok_or(RouteError::Process(ChannelDropped.into())),
+        }
+    }
+
+    /// Handle `command` with the process `id`, spawning it first if needed. See [`ProcessManager::locate`].
+    pub async fn route<T, C: Command>(&self, id: impl ToEntityId, command: C) -> Result<Result<T::Event, T::Rejection>, RouteError>
+    where
+        T: Process + CommandHandler<C>,
+    {
+        let refs = self.locate::<T>(id).await?;
+        Ok(refs.handle(command).await?)
+    }
 }
@@ -0,0 +1,71 @@
+#[cfg(feature = "metrics")]
+mod adapter;
+
+#[cfg(feature = "metrics")]
+pub use self::adapter::*;
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use nitinol_core::identifier::EntityId;
+
+use crate::lifecycle::Termination;
+
+/// Kind of task applied in a process mailbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskKind {
+    Command,
+    Event,
+    Entrust,
+    Receive,
+    Query,
+    /// Supervision and death watch notifications.
+    System,
+}
+
+impl TaskKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::Command => "command",
+            TaskKind::Event => "event",
+            TaskKind::Entrust => "entrust",
+            TaskKind::Receive => "receive",
+            TaskKind::Query => "query",
+            TaskKind::System => "system",
+        }
+    }
+}
+
+impl Display for TaskKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Hook receiving measurements from every running process.
+///
+/// Installed through [`ProcessManager::with_metrics`](crate::manager::ProcessManager::with_metrics).
+/// All methods do nothing by default.
+#[allow(unused_variables)]
+pub trait Metrics: 'static + Sync + Send {
+    /// Number of tasks still waiting in the mailbox, sampled each time a task is dequeued.
+    ///
+    /// System messages are not counted, as in [`Receptor::mailbox_len`](crate::Receptor::mailbox_len).
+    fn mailbox_depth(&self, id: &EntityId, depth: usize) {}
+
+    /// Time spent applying a single task.
+    fn task_processed(&self, id: &EntityId, kind: TaskKind, elapsed: Duration) {}
+
+    /// A command was rejected by its handler or an interceptor.
+    fn rejected(&self, id: &EntityId, kind: TaskKind) {}
+
+    /// A task still waiting in the mailbox was discarded because the process terminated.
+    fn dropped(&self, id: &EntityId, kind: TaskKind) {}
+
+    /// Time between the start and the termination of a process.
+    fn lifetime(&self, id: &EntityId, elapsed: Duration, reason: &Termination) {}
+}
+
+pub(crate) struct Disabled;
+
+impl Metrics for Disabled {}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use metrics::Label;
+use nitinol_core::identifier::EntityId;
+
+use super::{Metrics, TaskKind};
+use crate::lifecycle::Termination;
+
+/// [`Metrics`] implementation that forwards to the recorder installed for the `metrics` crate.
+///
+/// Recorded metrics:
+/// - `nitinol_process_mailbox_depth` (gauge, summed over every process unless labelled by `id`)
+/// - `nitinol_process_task_seconds` (histogram, labelled by `kind`)
+/// - `nitinol_process_rejections_total` (counter, labelled by `kind`)
+/// - `nitinol_process_dropped_total` (counter, labelled by `kind`)
+/// - `nitinol_process_lifetime_seconds` (histogram, labelled by `reason`)
+///
+/// The entity id is only attached as the `id` label when enabled with [`MetricsAdapter::with_entity_label`],
+/// since it is unbounded in most applications.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsAdapter {
+    entity_label: bool,
+    /// Last depth sampled for each running process, to keep the sum without an `id` label.
+    depths: Arc<Mutex<HashMap<EntityId, usize>>>,
+}
+
+impl MetricsAdapter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity_label(mut self) -> Self {
+        self.entity_label = true;
+        self
+    }
+
+    fn labels(&self, id: &EntityId, extra: Option<(&'static str, &'static str)>) -> Vec<Label> {
+        let mut labels = Vec::with_capacity(2);
+        if self.entity_label {
+            labels.push(Label::new("id", id.to_string()));
+        }
+        if let Some((key, value)) = extra {
+            labels.push(Label::new(key, value));
+        }
+        labels
+    }
+}
+
+impl Metrics for MetricsAdapter {
+    fn mailbox_depth(&self, id: &EntityId, depth: usize) {
+        let gauge = metrics::gauge!("nitinol_process_mailbox_depth", self.labels(id, None));
+        if self.entity_label {
+            gauge.set(depth as f64);
+            return;
+        }
+        let previous = self.depths.lock().unwrap().insert(id.clone(), depth).unwrap_or_default();
+        match depth >= previous {
+            true => gauge.increment((depth - previous) as f64),
+            false => gauge.decrement((previous - depth) as f64),
+        }
+    }
+
+    fn task_processed(&self, id: &EntityId, kind: TaskKind, elapsed: Duration) {
+        metrics::histogram!("nitinol_process_task_seconds", self.labels(id, Some(("kind", kind.as_str()))))
+            .record(elapsed.as_secs_f64());
+    }
+
+    fn rejected(&self, id: &EntityId, kind: TaskKind) {
+        metrics::counter!("nitinol_process_rejections_total", self.labels(id, Some(("kind", kind.as_str()))))
+            .increment(1);
+    }
+
+    fn dropped(&self, id: &EntityId, kind: TaskKind) {
+        metrics::counter!("nitinol_process_dropped_total", self.labels(id, Some(("kind", kind.as_str()))))
+            .increment(1);
+    }
+
+    fn lifetime(&self, id: &EntityId, elapsed: Duration, reason: &Termination) {
+        let gauge = metrics::gauge!("nitinol_process_mailbox_depth", self.labels(id, None));
+        if self.entity_label {
+            gauge.set(0.0);
+        } else if let Some(depth) = self.depths.lock().unwrap().remove(id) {
+            gauge.decrement(depth as f64);
+        }
+
+        let reason = match reason {
+            Termination::Stopped => "stopped",
+            Termination::Poisoned => "poisoned",
+            Termination::Timeout => "timeout",
+            Termination::Panicked(_) => "panicked",
+            Termination::Failed(_) => "failed",
+        };
+        metrics::histogram!("nitinol_process_lifetime_seconds", self.labels(id, Some(("reason", reason))))
+            .record(elapsed.as_secs_f64());
+    }
+}
@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use nitinol_core::identifier::EntityId;
 use crate::{Context, Receptor};
+use crate::lifecycle::Termination;
+use crate::supervisor::Directive;
 
 #[allow(unused_variables)]
 #[async_trait]
@@ -8,8 +10,19 @@ pub trait Process: 'static + Sync + Send + Sized {
     fn aggregate_id(&self) -> EntityId;
     async fn start(&self, ctx: &mut Context) {}
     async fn stop(&self, ctx: &mut Context) {}
-    
+
+    /// Called when a child spawned by [`Context::spawn_child`] fails a task.
+    ///
+    /// The returned [`Directive`] decides what happens to the child. Stops it by default.
+    async fn supervise(&mut self, child: &EntityId, reason: &str, ctx: &mut Context) -> Directive {
+        Directive::Stop
+    }
+
+    /// Called after a child spawned by [`Context::spawn_child`]
+    /// or a process watched with [`Context::watch`] has terminated.
+    async fn terminated(&mut self, id: &EntityId, reason: &Termination, ctx: &mut Context) {}
+
     async fn as_ref_self(&self, ctx: &Context) -> Option<Receptor<Self>> {
-        ctx.find(&self.aggregate_id()).await
+        ctx.find(ctx.id()).await
     }
 }
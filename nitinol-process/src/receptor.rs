@@ -1,8 +1,9 @@
 use std::any::Any;
-use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::oneshot;
+use std::future::Future;
+use tokio::sync::{oneshot, watch};
 use nitinol_core::command::Command;
 use nitinol_core::event::Event;
+use nitinol_core::identifier::EntityId;
 
 use crate::task::{
     TaskApplier, 
@@ -13,10 +14,17 @@ use crate::task::{
     CommandHandler,
     EventApplicator,
     Receive,
+    Query,
+    QueryTask,
+    StopTask,
+    InfoTask,
 };
-use crate::errors::ChannelDropped;
+use crate::errors::{ChannelDropped, Poisoned, ProcessError};
+use crate::lifecycle::{ProcessInfo, Termination};
+use crate::mailbox::{Priority, Sender};
 use crate::message::Message;
-use crate::Process;
+use crate::context::Views;
+use crate::{Process, Status};
 
 pub mod any;
 
@@ -24,66 +32,186 @@ use self::any::DynRef;
 
 #[derive(Debug)]
 pub struct Receptor<T: Process> {
-    pub(crate) channel: UnboundedSender<Box<dyn TaskApplier<T>>>
+    pub(crate) id: EntityId,
+    pub(crate) channel: Sender<T>,
+    pub(crate) terminated: watch::Receiver<Option<Termination>>,
+    pub(crate) status: Status,
+    pub(crate) views: Views,
+}
+
+impl<T: Process> Receptor<T> {
+    /// Identifier under which the process is registered.
+    pub fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    /// Returns the reason the process terminated, or `None` while it is still running.
+    pub fn termination(&self) -> Option<Termination> {
+        self.terminated.borrow().clone()
+    }
+
+    /// Number of commands, events and messages waiting in the mailbox of the process.
+    ///
+    /// Stop requests and supervision notifications are not counted.
+    pub fn mailbox_len(&self) -> usize {
+        self.channel.len()
+    }
+
+    /// Latest view of type `V` published with [`Context::publish`](crate::Context::publish),
+    /// or `None` if the process has not published one yet.
+    pub fn snapshot<V: Clone + Sync + Send + 'static>(&self) -> Option<watch::Receiver<V>> {
+        self.views.subscribe()
+    }
+
+    /// Wait until the process terminates and return the reason.
+    ///
+    /// Resolves immediately if the process has already terminated.
+    pub fn watch(&self) -> impl Future<Output = Termination> + Send + 'static {
+        let mut terminated = self.terminated.clone();
+        async move {
+            match terminated.wait_for(Option::is_some).await {
+                Ok(reason) => reason.clone().expect("checked by `wait_for`"),
+                Err(_) => Termination::Failed("lifecycle dropped without reporting termination.".to_string()),
+            }
+        }
+    }
 }
 
 #[rustfmt::skip]
 impl<T: Process> Receptor<T> {
-    pub async fn handle<C: Command>(&self, command: C) -> Result<Result<T::Event, T::Rejection>, ChannelDropped>
+    /// Handle `command`, returning the event to pass to [`Receptor::apply`].
+    ///
+    /// A command carrying a [`CommandId`](nitinol_core::command::CommandId) is handled once its event is applied.
+    /// Sent again after that, it returns the original event, and applying that event again does nothing.
+    pub async fn handle<C: Command>(&self, command: C) -> Result<Result<T::Event, T::Rejection>, ProcessError>
     where
         T: CommandHandler<C>,
     {
         let (tx, rx) = oneshot::channel();
-        self.channel
-            .send(Box::new(CommandHandleTask {
+        self.enqueue(Box::new(CommandHandleTask {
                 command,
                 oneshot: tx,
             }))
-            .map_err(|_| ChannelDropped)?;
+            .await?;
 
-        rx.await.map_err(|_| ChannelDropped)
+        match rx.await {
+            Ok(result) => result,
+            Err(_) => Err(self.closed().await),
+        }
     }
 
-    pub async fn apply<E: Event>(&self, event: E) -> Result<(), ChannelDropped>
+    pub async fn apply<E: Event>(&self, event: E) -> Result<(), ProcessError>
     where
         T: EventApplicator<E>,
     {
         let (tx, rx) = oneshot::channel();
-        self.channel
-            .send(Box::new(EventApplicatorTask { event, oneshot: tx }))
-            .map_err(|_| ChannelDropped)?;
+        self.enqueue(Box::new(EventApplicatorTask { event, oneshot: tx }))
+            .await?;
 
-        rx.await.map_err(|_| ChannelDropped)
+        match rx.await {
+            Ok(result) => result,
+            Err(_) => Err(self.closed().await),
+        }
     }
     
-    pub async fn entrust<C: Command>(&self, cmd: C) -> Result<(), ChannelDropped>
+    pub async fn entrust<C: Command>(&self, cmd: C) -> Result<(), ProcessError>
     where
         T: CommandHandler<C>,
         T: EventApplicator<<T as CommandHandler<C>>::Event>,
     {
-        self.channel
-            .send(Box::new(EntrustTask { command: cmd }))
-            .map_err(|_| ChannelDropped)?;
-        
-        Ok(())
+        self.enqueue(Box::new(EntrustTask { command: cmd }))
+            .await
+    }
+    
+    pub async fn send<M>(&self, message: M) -> Result<(), ProcessError>
+    where
+        T: Receive<M>,
+        M: Message
+    {
+        self.send_with(message, Priority::Normal)
+            .await
     }
     
-    pub async fn send<M>(&self, message: M) -> Result<(), ChannelDropped>
+    /// Same as [`Receptor::send`], queueing `message` in the lane of `priority`.
+    pub async fn send_with<M>(&self, message: M, priority: Priority) -> Result<(), ProcessError>
     where
         T: Receive<M>,
         M: Message
     {
-        self.channel
-            .send(Box::new(ReceiveTask { message }))
-            .map_err(|_| ChannelDropped)?;
+        if self.channel.send(Box::new(ReceiveTask { message }), priority).is_err() {
+            return Err(self.closed().await);
+        }
         Ok(())
     }
+    
+    /// Answer `query` from the current state.
+    ///
+    /// Queries are queued behind the tasks already waiting in the mailbox, so they observe
+    /// every command, event and message sent before them. To read without waiting for the
+    /// mailbox, publish a view with [`Context::publish`](crate::Context::publish) and read it
+    /// through [`Receptor::snapshot`] instead.
+    pub async fn query<Q>(&self, query: Q) -> Result<T::Output, ProcessError>
+    where
+        T: Query<Q>,
+        Q: 'static + Sync + Send,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.enqueue(Box::new(QueryTask { query, oneshot: tx })).await?;
+
+        match rx.await {
+            Ok(result) => result,
+            Err(_) => Err(self.closed().await),
+        }
+    }
+    
+    /// Ask the process to stop ahead of the tasks waiting in its mailbox.
+    ///
+    /// Queued tasks are discarded. Use [`Receptor::watch`] to wait for the termination.
+    pub async fn stop(&self) -> Result<(), ProcessError> {
+        if self.channel.send_system(Box::new(StopTask)).is_err() {
+            return Err(self.closed().await);
+        }
+        Ok(())
+    }
+    
+    /// Query the state of the process ahead of the tasks waiting in its mailbox.
+    pub async fn info(&self) -> Result<ProcessInfo, ProcessError> {
+        let (tx, rx) = oneshot::channel();
+        if self.channel.send_system(Box::new(InfoTask { oneshot: tx })).is_err() {
+            return Err(self.closed().await);
+        }
+
+        match rx.await {
+            Ok(info) => Ok(info),
+            Err(_) => Err(self.closed().await),
+        }
+    }
+
+    async fn enqueue(&self, task: Box<dyn TaskApplier<T>>) -> Result<(), ProcessError> {
+        if self.channel.send(task, Priority::Normal).is_err() {
+            return Err(self.closed().await);
+        }
+        Ok(())
+    }
+
+    /// Error explaining why the mailbox no longer accepts or answers tasks.
+    async fn closed(&self) -> ProcessError {
+        if self.status.is_active().await {
+            ChannelDropped.into()
+        } else {
+            Poisoned(self.id.clone()).into()
+        }
+    }
 }
 
 impl<T: Process> Clone for Receptor<T> {
     fn clone(&self) -> Self {
-        Self { 
-            channel: self.channel.clone()
+        Self {
+            id: self.id.clone(),
+            channel: self.channel.clone(),
+            terminated: self.terminated.clone(),
+            status: self.status.clone(),
+            views: self.views.clone(),
         }
     }
 }
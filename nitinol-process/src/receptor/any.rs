@@ -17,6 +17,11 @@ impl AnyRef {
             .cloned()
             .ok_or(InvalidCast { to: type_name::<T>() })
     }
+
+    /// Whether both refer to the same registration.
+    pub(crate) fn same(&self, other: &AnyRef) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 impl Clone for AnyRef {
@@ -1,8 +1,9 @@
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use tokio::sync::RwLock;
-use nitinol_core::identifier::EntityId;
+use nitinol_core::identifier::{EntityId, TypedId};
 
 use crate::any::AnyRef;
 use crate::errors::{AlreadyExist, NotFound, InvalidCast};
@@ -13,42 +14,48 @@ pub struct ProcessRegistry {
 }
 
 impl ProcessRegistry {
+    /// Register `writer` under `id`, returning the entry to pass back to [`ProcessRegistry::deregister`].
+    ///
+    /// The check and the insertion happen under the same lock, so only one of concurrent registrations of `id` succeeds.
     pub(crate) async fn register<T: Process>(
         &self,
         id: EntityId,
         writer: Receptor<T>,
-    ) -> Result<(), AlreadyExist> {
-        let lock = self.registry.read().await;
-        if lock.iter().any(|(exist, _)| exist.eq(&id)) {
-            return Err(AlreadyExist(id));
-        }
-
-        drop(lock); // release lock
-        
+    ) -> Result<AnyRef, AlreadyExist> {
         let mut lock = self.registry.write().await;
-        lock.insert(id.clone(), writer.into());
+        let Entry::Vacant(entry) = lock.entry(id.clone()) else {
+            return Err(AlreadyExist(id));
+        };
+        let registered = entry.insert(writer.into()).clone();
 
         tracing::info!(name: "Registry", "Registered: {}", id);
         
-        Ok(())
+        Ok(registered)
     }
 
-    pub(crate) async fn deregister(&self, id: &EntityId) -> Result<(), NotFound> {
-        let lock = self.registry.read().await;
-        if !lock.iter().any(|(exist, _)| exist.eq(id)) {
-            return Err(NotFound(id.to_owned()));
-        }
-
-        drop(lock); // release lock
-
+    /// Remove `id` if it is still the entry `registered`, and not a process registered under the same id since.
+    pub(crate) async fn deregister(&self, id: &EntityId, registered: &AnyRef) -> Result<(), NotFound> {
         let mut lock = self.registry.write().await;
-        lock.remove(id);
+        match lock.get(id) {
+            Some(current) if current.same(registered) => {
+                lock.remove(id);
+            }
+            _ => return Err(NotFound(id.to_owned())),
+        }
 
         tracing::info!(name: "Registry", "Deregistered: {}", id);
         
         Ok(())
     }
 
+    /// Registered ids whose text parses as an `I`.
+    pub(crate) async fn ids<I: TypedId>(&self) -> Vec<I> {
+        let lock = self.registry.read().await;
+        lock.keys()
+            .filter_map(|id| id.parse::<I>().ok())
+            .collect()
+    }
+
     #[rustfmt::skip]
     pub async fn find<T: Process>(&self, id: &EntityId) -> Result<Option<Receptor<T>>, InvalidCast> {
         let lock = self.registry.read().await;
@@ -0,0 +1,332 @@
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+use tokio::sync::{Mutex, RwLock};
+use nitinol_core::command::Command;
+use nitinol_core::event::Event;
+use nitinol_core::identifier::EntityId;
+
+use crate::errors::RouteError;
+use crate::factory::Factory;
+use crate::lifecycle::Termination;
+use crate::manager::ProcessManager;
+use crate::message::Message;
+use crate::task::{CommandHandler, EventApplicator, Query, Receive};
+use crate::{Process, Receptor};
+
+/// Points each routee occupies on the consistent hash ring.
+const VIRTUAL_NODES: usize = 64;
+
+/// How a [`Router`] picks the routee of each task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    RoundRobin,
+    Random,
+    /// The routee with the fewest tasks waiting in its mailbox.
+    SmallestMailbox,
+    /// The routee owning the key on a hash ring, see [`Router::routee_for`].
+    ///
+    /// Tasks must be routed with a key, [`Router::routee`] and the methods routing
+    /// through it fail with [`RouteError::KeyRequired`].
+    ConsistentHash,
+}
+
+/// Pool of identical processes behind one address.
+///
+/// Created by [`ProcessManager::pool`]. The routees are registered as `{router}/{index}`.
+///
+/// A routee that panics or fails is spawned again under the same id through the factory,
+/// one that terminates for another reason, e.g. stopped or poisoned, leaves the pool.
+pub struct Router<T: Process> {
+    id: EntityId,
+    strategy: Strategy,
+    manager: ProcessManager,
+    factory: Arc<dyn Factory<T>>,
+    routees: Arc<RwLock<Routees<T>>>,
+    /// Held while routees are spawned or stopped, which happens outside of `routees`.
+    changing: Arc<Mutex<()>>,
+    cursor: Arc<AtomicUsize>,
+}
+
+/// [`Router`] held by the tasks watching its routees, which must not keep them alive.
+struct WeakRouter<T: Process> {
+    id: EntityId,
+    strategy: Strategy,
+    manager: ProcessManager,
+    factory: Arc<dyn Factory<T>>,
+    routees: Weak<RwLock<Routees<T>>>,
+    changing: Arc<Mutex<()>>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl<T: Process> WeakRouter<T> {
+    fn upgrade(&self) -> Option<Router<T>> {
+        Some(Router {
+            id: self.id.clone(),
+            strategy: self.strategy,
+            manager: self.manager.clone(),
+            factory: Arc::clone(&self.factory),
+            routees: self.routees.upgrade()?,
+            changing: Arc::clone(&self.changing),
+            cursor: Arc::clone(&self.cursor),
+        })
+    }
+}
+
+struct Routees<T: Process> {
+    members: Vec<Receptor<T>>,
+    ring: BTreeMap<u64, usize>,
+}
+
+impl<T: Process> Routees<T> {
+    fn rebuild(&mut self) {
+        self.ring = self.members
+            .iter()
+            .enumerate()
+            .flat_map(|(index, member)| (0..VIRTUAL_NODES).map(move |node| (hash(&(member.id(), node)), index)))
+            .collect();
+    }
+}
+
+fn hash<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<T: Process> Router<T> {
+    pub(crate) async fn new(id: EntityId, size: usize, strategy: Strategy, manager: ProcessManager, factory: Arc<dyn Factory<T>>) -> Result<Router<T>, RouteError> {
+        let router = Self {
+            id,
+            strategy,
+            manager,
+            factory,
+            routees: Arc::new(RwLock::new(Routees { members: Vec::new(), ring: BTreeMap::new() })),
+            changing: Arc::new(Mutex::new(())),
+            cursor: Arc::new(AtomicUsize::new(0)),
+        };
+        router.resize(size).await?;
+        Ok(router)
+    }
+
+    pub fn id(&self) -> &EntityId {
+        &self.id
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    pub async fn size(&self) -> usize {
+        self.routees.read().await.members.len()
+    }
+
+    /// Spawn or stop routees until the pool holds `size` of them.
+    ///
+    /// Stopped routees discard the tasks still waiting in their mailbox.
+    /// Returns once they have terminated, so that their ids can be reused right away.
+    ///
+    /// Tasks keep being routed to the other routees in the meantime.
+    pub async fn resize(&self, size: usize) -> Result<(), RouteError> {
+        let _changing = self.changing.lock().await;
+        let (stopped, ids) = {
+            let mut routees = self.routees.write().await;
+            let kept = size.min(routees.members.len());
+            let stopped = routees.members.split_off(kept);
+            routees.rebuild();
+            let taken = routees.members.iter().map(|member| member.id().clone()).collect::<HashSet<_>>();
+            let ids = (0..)
+                .map(|index| EntityId::new(format!("{}/{}", self.id, index)))
+                .filter(|id| !taken.contains(id))
+                .take(size - routees.members.len())
+                .collect::<Vec<_>>();
+            (stopped, ids)
+        };
+
+        for refs in stopped {
+            if refs.stop().await.is_ok() {
+                refs.watch().await;
+            }
+        }
+
+        let mut spawned = Vec::new();
+        let mut result = Ok(());
+        for id in ids {
+            match self.spawn(id).await {
+                Ok(refs) => spawned.push(refs),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        if !spawned.is_empty() {
+            let mut routees = self.routees.write().await;
+            routees.members.extend(spawned);
+            routees.rebuild();
+        }
+        result
+    }
+
+    async fn spawn(&self, id: EntityId) -> Result<Receptor<T>, RouteError> {
+        let replayed = self.factory.create(&id).await
+            .map_err(|source| RouteError::Factory { id: id.clone(), source })?;
+        let refs = self.manager.spawn_as(id, replayed).await?;
+        self.watch(&refs);
+        Ok(refs)
+    }
+
+    /// Replace `routee` once it terminates, see [`Router`].
+    fn watch(&self, routee: &Receptor<T>) {
+        let id = routee.id().clone();
+        let termination = routee.watch();
+        let router = self.downgrade();
+        tokio::spawn(async move {
+            let reason = termination.await;
+            if let Some(router) = router.upgrade() {
+                router.replace(id, reason).await;
+            }
+        });
+    }
+
+    async fn replace(&self, id: EntityId, reason: Termination) {
+        let _changing = self.changing.lock().await;
+        let terminated = |member: &Receptor<T>| member.id() == &id && member.termination().is_some();
+        // Routees stopped by `resize` have already left the pool.
+        if !self.routees.read().await.members.iter().any(terminated) {
+            return;
+        }
+
+        let respawned = match reason {
+            Termination::Panicked(_) | Termination::Failed(_) => match self.spawn(id.clone()).await {
+                Ok(refs) => Some(refs),
+                Err(e) => {
+                    tracing::error!("Failed to respawn routee {id}: {e}");
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let mut routees = self.routees.write().await;
+        let Some(index) = routees.members.iter().position(terminated) else {
+            return;
+        };
+        match respawned {
+            Some(refs) => routees.members[index] = refs,
+            None => {
+                routees.members.remove(index);
+                routees.rebuild();
+            }
+        }
+    }
+
+    fn downgrade(&self) -> WeakRouter<T> {
+        WeakRouter {
+            id: self.id.clone(),
+            strategy: self.strategy,
+            manager: self.manager.clone(),
+            factory: Arc::clone(&self.factory),
+            routees: Arc::downgrade(&self.routees),
+            changing: Arc::clone(&self.changing),
+            cursor: Arc::clone(&self.cursor),
+        }
+    }
+
+    /// Routee chosen by the strategy of this router.
+    ///
+    /// Fails with [`RouteError::KeyRequired`] for [`Strategy::ConsistentHash`], see [`Router::routee_for`].
+    pub async fn routee(&self) -> Result<Receptor<T>, RouteError> {
+        let routees = self.routees.read().await;
+        let members = &routees.members;
+        if members.is_empty() {
+            return Err(RouteError::NoRoutee(self.id.clone()));
+        }
+        let index = match self.strategy {
+            Strategy::ConsistentHash => return Err(RouteError::KeyRequired(self.id.clone())),
+            Strategy::RoundRobin => self.cursor.fetch_add(1, Ordering::Relaxed) % members.len(),
+            Strategy::Random => {
+                let seed = self.cursor.fetch_add(1, Ordering::Relaxed);
+                RandomState::new().hash_one(seed) as usize % members.len()
+            }
+            Strategy::SmallestMailbox => members
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, member)| member.mailbox_len())
+                .map(|(index, _)| index)
+                .unwrap_or_default(),
+        };
+        Ok(members[index].clone())
+    }
+
+    /// Routee owning `key` on the hash ring, whatever the strategy of this router.
+    ///
+    /// The same key keeps reaching the same routee as long as the pool is not resized.
+    pub async fn routee_for<K: Hash + ?Sized>(&self, key: &K) -> Result<Receptor<T>, RouteError> {
+        let routees = self.routees.read().await;
+        let point = hash(key);
+        let index = routees.ring
+            .range(point..)
+            .chain(routees.ring.iter())
+            .map(|(_, index)| *index)
+            .next()
+            .ok_or_else(|| RouteError::NoRoutee(self.id.clone()))?;
+        Ok(routees.members[index].clone())
+    }
+}
+
+impl<T: Process> Router<T> {
+    pub async fn handle<C: Command>(&self, command: C) -> Result<Result<T::Event, T::Rejection>, RouteError>
+    where
+        T: CommandHandler<C>,
+    {
+        Ok(self.routee().await?.handle(command).await?)
+    }
+
+    pub async fn apply<E: Event>(&self, event: E) -> Result<(), RouteError>
+    where
+        T: EventApplicator<E>,
+    {
+        Ok(self.routee().await?.apply(event).await?)
+    }
+
+    pub async fn entrust<C: Command>(&self, command: C) -> Result<(), RouteError>
+    where
+        T: CommandHandler<C>,
+        T: EventApplicator<<T as CommandHandler<C>>::Event>,
+    {
+        Ok(self.routee().await?.entrust(command).await?)
+    }
+
+    pub async fn send<M: Message>(&self, message: M) -> Result<(), RouteError>
+    where
+        T: Receive<M>,
+    {
+        Ok(self.routee().await?.send(message).await?)
+    }
+
+    pub async fn query<Q>(&self, query: Q) -> Result<T::Output, RouteError>
+    where
+        T: Query<Q>,
+        Q: 'static + Sync + Send,
+    {
+        Ok(self.routee().await?.query(query).await?)
+    }
+}
+
+impl<T: Process> Clone for Router<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            strategy: self.strategy,
+            manager: self.manager.clone(),
+            factory: Arc::clone(&self.factory),
+            routees: Arc::clone(&self.routees),
+            changing: Arc::clone(&self.changing),
+            cursor: Arc::clone(&self.cursor),
+        }
+    }
+}
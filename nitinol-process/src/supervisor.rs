@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::WeakUnboundedSender;
+use tokio::sync::{oneshot, watch, Notify};
+use nitinol_core::identifier::EntityId;
+
+use crate::lifecycle::Termination;
+use crate::task::{SuperviseTask, TaskApplier, TerminatedTask};
+use crate::Process;
+
+/// Decision returned by a parent process when one of its children fails a task.
+///
+/// See [`Process::supervise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    /// Discard the failed task and keep the child running.
+    Resume,
+    /// Stop the child.
+    Stop,
+    /// Stop the child and fail the supervising process itself,
+    /// which is then handled by its own parent or [`FailurePolicy`] like any other failure.
+    Escalate,
+}
+
+/// What a process without a supervising parent does after one of its tasks fails or panics.
+///
+/// Configured through [`ProcessManager::with_policy`](crate::manager::ProcessManager::with_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Discard the failed task and keep processing the mailbox.
+    Continue,
+    /// Stop the process.
+    #[default]
+    Stop,
+}
+
+/// Type-erased handle that lets a child reach its parent without knowing the parent type.
+#[async_trait]
+pub(crate) trait Link: 'static + Sync + Send {
+    async fn supervise(&self, child: EntityId, reason: String) -> Directive;
+    fn terminated(&self, id: EntityId, reason: Termination);
+}
+
+pub(crate) struct ProcessLink<T: Process> {
+    pub(crate) channel: WeakUnboundedSender<Box<dyn TaskApplier<T>>>,
+}
+
+#[async_trait]
+impl<T: Process> Link for ProcessLink<T> {
+    async fn supervise(&self, child: EntityId, reason: String) -> Directive {
+        let Some(channel) = self.channel.upgrade() else {
+            return Directive::Stop;
+        };
+
+        let (tx, rx) = oneshot::channel();
+        if channel.send(Box::new(SuperviseTask { child, reason, oneshot: tx })).is_err() {
+            return Directive::Stop;
+        }
+
+        rx.await.unwrap_or(Directive::Stop)
+    }
+
+    fn terminated(&self, id: EntityId, reason: Termination) {
+        if let Some(channel) = self.channel.upgrade() {
+            let _ = channel.send(Box::new(TerminatedTask { id, reason }));
+        }
+    }
+}
+
+/// Handle held by a parent to stop a child and wait for its termination.
+pub(crate) struct ChildHandle {
+    pub(crate) shutdown: Arc<Notify>,
+    pub(crate) terminated: watch::Receiver<Option<Termination>>,
+}
+
+impl ChildHandle {
+    pub(crate) async fn stop(mut self) {
+        self.shutdown.notify_one();
+        let _ = self.terminated.wait_for(Option::is_some).await;
+    }
+}
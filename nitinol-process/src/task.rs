@@ -2,18 +2,76 @@ mod event;
 mod command;
 mod entrust;
 mod receive;
+mod query;
+mod supervise;
+mod system;
 
 pub use self::event::*;
 pub use self::command::*;
 pub use self::entrust::*;
 pub use self::receive::*;
+pub use self::query::*;
+
+pub(crate) use self::supervise::*;
+pub(crate) use self::system::*;
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
 
 use async_trait::async_trait;
+use futures_util::FutureExt;
 
 use crate::{Process, Context};
-use crate::errors::ChannelDropped;
+use crate::errors::{Panicked, ProcessError};
+use crate::metrics::TaskKind;
+
+/// Result of a task that was applied without failing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Accepted,
+    /// The command handler returned a rejection.
+    Rejected,
+}
 
+#[allow(unused_variables)]
 #[async_trait]
 pub trait TaskApplier<T: Process>: 'static + Sync + Send {
-    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<(), ChannelDropped>;
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError>;
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::System
+    }
+
+    /// Type name of the command, event or message carried by this task, used in tracing spans.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Whether the [`Behavior`](crate::behavior::Behavior) active in `ctx` handles this task.
+    /// Such tasks are applied even while the process is stashing.
+    fn handled_by_behavior(&self, ctx: &Context) -> bool {
+        false
+    }
+
+    /// Called instead of [`TaskApplier::apply`] for tasks still queued when the process terminates.
+    fn reject(self: Box<Self>, error: ProcessError) {}
+}
+
+/// Run user code, turning a panic into [`Panicked`] instead of unwinding the process loop.
+pub(crate) async fn isolate<F: Future>(f: F) -> Result<F::Output, Panicked> {
+    AssertUnwindSafe(f)
+        .catch_unwind()
+        .await
+        .map_err(|payload| Panicked(panic_message(payload)))
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
 }
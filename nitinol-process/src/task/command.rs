@@ -1,6 +1,7 @@
 use std::fmt::Debug;
-use super::TaskApplier;
-use crate::errors::ChannelDropped;
+use super::{isolate, Outcome, TaskApplier};
+use crate::errors::ProcessError;
+use crate::metrics::TaskKind;
 use crate::{Process, Context};
 use async_trait::async_trait;
 use nitinol_core::command::Command;
@@ -14,12 +15,13 @@ pub trait CommandHandler<C: Command>: 'static + Sync + Send {
     async fn handle(&self, command: C, ctx: &mut Context) -> Result<Self::Event, Self::Rejection>;
 }
 
+#[allow(clippy::type_complexity)]
 pub(crate) struct CommandHandleTask<C: Command, T: Process>
 where
     T: CommandHandler<C>,
 {
     pub(crate) command: C,
-    pub(crate) oneshot: oneshot::Sender<Result<T::Event, T::Rejection>>,
+    pub(crate) oneshot: oneshot::Sender<Result<Result<T::Event, T::Rejection>, ProcessError>>,
 }
 
 #[async_trait::async_trait]
@@ -27,10 +29,38 @@ impl<C: Command, T: Process> TaskApplier<T> for CommandHandleTask<C, T>
 where
     T: CommandHandler<C>,
 {
-    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<(), ChannelDropped> {
-        self.oneshot
-            .send(state.handle(self.command, ctx).await)
-            .map_err(|_| ChannelDropped)?;
-        Ok(())
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        let command_id = self.command.command_id();
+        if let Some(event) = command_id.as_ref().and_then(|id| ctx.duplicate(id)) {
+            ctx.reply(self.oneshot, Ok(event));
+            return Ok(Outcome::Accepted);
+        }
+
+        match isolate(state.handle(self.command, ctx)).await {
+            Ok(result) => {
+                if let (Some(id), Ok(event)) = (command_id, &result) {
+                    ctx.await_apply(id, event);
+                }
+                let outcome = if result.is_ok() { Outcome::Accepted } else { Outcome::Rejected };
+                ctx.reply(self.oneshot, result);
+                Ok(outcome)
+            }
+            Err(panicked) => {
+                let _ = self.oneshot.send(Err(panicked.clone().into()));
+                Err(panicked.into())
+            }
+        }
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Command
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<C>()
+    }
+
+    fn reject(self: Box<Self>, error: ProcessError) {
+        let _ = self.oneshot.send(Err(error));
     }
 }
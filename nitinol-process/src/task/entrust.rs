@@ -2,9 +2,10 @@ use std::fmt::Debug;
 
 use async_trait::async_trait;
 use nitinol_core::command::Command;
-use crate::errors::ChannelDropped;
+use crate::errors::ProcessError;
+use crate::metrics::TaskKind;
 use crate::{Context, Process};
-use crate::task::{CommandHandler, EventApplicator, TaskApplier};
+use crate::task::{isolate, Outcome, CommandHandler, EventApplicator, TaskApplier};
 
 pub struct EntrustTask<C: Command> {
     pub(crate) command: C,
@@ -17,16 +18,40 @@ where
     T::Rejection: Debug,
     T: EventApplicator<<T as CommandHandler<C>>::Event>,
 {
-    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<(), ChannelDropped> {
-        match state.handle(self.command, ctx).await {
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        let command_id = self.command.command_id();
+        if let Some(id) = &command_id {
+            if ctx.dedup.get(id).is_some() {
+                tracing::debug!("Skipped duplicate command {id}");
+                return Ok(Outcome::Accepted);
+            }
+        }
+
+        match isolate(state.handle(self.command, ctx)).await? {
             Ok(event) => {
-                state.apply(event, ctx).await;
+                if let Some(id) = command_id {
+                    ctx.record(id, &event);
+                }
+                isolate(state.apply(event, ctx)).await?;
                 ctx.sequence += 1;
+                Ok(Outcome::Accepted)
             }
             Err(rejection) => {
                 tracing::error!("An error occurred: {:?}", rejection);
+                Ok(Outcome::Rejected)
             }
         }
-        Ok(())
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Entrust
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<C>()
+    }
+
+    fn reject(self: Box<Self>, error: ProcessError) {
+        tracing::warn!("Discarded entrusted command: {error}");
     }
 }
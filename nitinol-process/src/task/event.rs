@@ -1,10 +1,15 @@
-use super::TaskApplier;
+use super::{isolate, Outcome, TaskApplier};
 use crate::{Process, Context};
 use async_trait::async_trait;
 use nitinol_core::event::Event;
 use tokio::sync::oneshot;
-use crate::errors::ChannelDropped;
+use crate::errors::ProcessError;
+use crate::metrics::TaskKind;
 
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not apply `{E}`",
+    note = "implement `EventApplicator<{E}>`, or add an `#[applies]` method taking it to the `#[aggregate]` impl block",
+)]
 #[async_trait]
 pub trait EventApplicator<E: Event>: 'static + Sync + Send {
     async fn apply(&mut self, event: E, ctx: &mut Context);
@@ -12,7 +17,7 @@ pub trait EventApplicator<E: Event>: 'static + Sync + Send {
 
 pub(crate) struct EventApplicatorTask<E: Event> {
     pub(crate) event: E,
-    pub(crate) oneshot: oneshot::Sender<()>,
+    pub(crate) oneshot: oneshot::Sender<Result<(), ProcessError>>,
 }
 
 #[async_trait]
@@ -20,11 +25,30 @@ impl<E: Event, T: Process> TaskApplier<T> for EventApplicatorTask<E>
 where
     T: EventApplicator<E>,
 {
-    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<(), ChannelDropped> {
-        self.oneshot
-            .send(state.apply(self.event, ctx).await)
-            .map_err(|_| ChannelDropped)?;
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        if !ctx.claim(&self.event) {
+            tracing::debug!("Skipped event of a duplicate command");
+            ctx.reply(self.oneshot, ());
+            return Ok(Outcome::Accepted);
+        }
+        if let Err(panicked) = isolate(state.apply(self.event, ctx)).await {
+            let _ = self.oneshot.send(Err(panicked.clone().into()));
+            return Err(panicked.into());
+        }
+        ctx.reply(self.oneshot, ());
         ctx.sequence += 1;
-        Ok(())
+        Ok(Outcome::Accepted)
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Event
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<E>()
+    }
+
+    fn reject(self: Box<Self>, error: ProcessError) {
+        let _ = self.oneshot.send(Err(error));
     }
 }
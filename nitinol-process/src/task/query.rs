@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+
+use crate::errors::ProcessError;
+use crate::metrics::TaskKind;
+use crate::task::{isolate, Outcome, TaskApplier};
+use crate::{Context, Process};
+
+/// Read-only request answered from `&self`, see [`Receptor::query`](crate::Receptor::query).
+#[async_trait]
+pub trait Query<Q>: 'static + Sync + Send
+where
+    Q: 'static + Sync + Send,
+{
+    type Output: 'static + Sync + Send;
+    async fn query(&self, query: Q, ctx: &Context) -> Self::Output;
+}
+
+pub(crate) struct QueryTask<Q, T>
+where
+    Q: 'static + Sync + Send,
+    T: Query<Q>,
+{
+    pub(crate) query: Q,
+    pub(crate) oneshot: oneshot::Sender<Result<T::Output, ProcessError>>,
+}
+
+#[async_trait]
+impl<Q, T: Process> TaskApplier<T> for QueryTask<Q, T>
+where
+    Q: 'static + Sync + Send,
+    T: Query<Q>,
+{
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        match isolate(Query::query(&*state, self.query, ctx)).await {
+            Ok(output) => {
+                let _ = self.oneshot.send(Ok(output));
+                Ok(Outcome::Accepted)
+            }
+            Err(panicked) => {
+                let _ = self.oneshot.send(Err(panicked.clone().into()));
+                Err(panicked.into())
+            }
+        }
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Query
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Q>()
+    }
+
+    fn reject(self: Box<Self>, error: ProcessError) {
+        let _ = self.oneshot.send(Err(error));
+    }
+}
@@ -1,9 +1,12 @@
 use std::fmt::Debug;
+use std::sync::Arc;
 use async_trait::async_trait;
+use crate::behavior::{Behavior, BehaviorHandler};
 use crate::{Context, Process};
-use crate::errors::ChannelDropped;
+use crate::errors::{ProcessError, ReceiveFailed};
+use crate::metrics::TaskKind;
 use crate::message::Message;
-use crate::task::TaskApplier;
+use crate::task::{isolate, Outcome, TaskApplier};
 
 #[async_trait]
 pub trait Receive<M: Message>: 'static + Sync + Send {
@@ -24,9 +27,35 @@ where
     T: Receive<M>,
     M: Message
 {
-    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<(), ChannelDropped> {
-        // FIXME: Improve error handling. :/
-        state.receive(self.message, ctx).await.unwrap();
-        Ok(())
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        match handler::<T, M>(ctx) {
+            Some(handler) => isolate(handler.receive(state, self.message, ctx)).await?,
+            None => isolate(state.receive(self.message, ctx)).await?
+                .map_err(|e| ReceiveFailed { message: std::any::type_name::<M>(), reason: format!("{e:?}") })?,
+        }
+        Ok(Outcome::Accepted)
     }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Receive
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<M>()
+    }
+
+    fn handled_by_behavior(&self, ctx: &Context) -> bool {
+        handler::<T, M>(ctx).is_some()
+    }
+
+    fn reject(self: Box<Self>, error: ProcessError) {
+        tracing::warn!("Discarded message: {error}");
+    }
+}
+
+fn handler<T: Process, M: Message>(ctx: &Context) -> Option<Arc<dyn BehaviorHandler<M, T>>> {
+    ctx.behavior
+        .as_ref()?
+        .downcast_ref::<Behavior<T>>()?
+        .handler::<M>()
 }
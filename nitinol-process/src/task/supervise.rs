@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+use tokio::sync::oneshot;
+
+use crate::errors::{ChannelDropped, Escalated, ProcessError};
+use crate::lifecycle::Termination;
+use crate::supervisor::Directive;
+use crate::task::{isolate, Outcome, TaskApplier};
+use crate::{Context, Process};
+
+pub(crate) struct SuperviseTask {
+    pub(crate) child: EntityId,
+    pub(crate) reason: String,
+    pub(crate) oneshot: oneshot::Sender<Directive>,
+}
+
+#[async_trait]
+impl<T: Process> TaskApplier<T> for SuperviseTask {
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        let directive = match isolate(state.supervise(&self.child, &self.reason, ctx)).await {
+            Ok(directive) => directive,
+            Err(panicked) => {
+                let _ = self.oneshot.send(Directive::Stop);
+                return Err(panicked.into());
+            }
+        };
+        self.oneshot
+            .send(directive)
+            .map_err(|_| ChannelDropped)?;
+        if directive == Directive::Escalate {
+            return Err(Escalated { child: self.child, reason: self.reason }.into());
+        }
+        Ok(Outcome::Accepted)
+    }
+}
+
+pub(crate) struct TerminatedTask {
+    pub(crate) id: EntityId,
+    pub(crate) reason: Termination,
+}
+
+#[async_trait]
+impl<T: Process> TaskApplier<T> for TerminatedTask {
+    async fn apply(self: Box<Self>, state: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        ctx.children.remove(&self.id);
+        isolate(state.terminated(&self.id, &self.reason, ctx)).await?;
+        Ok(Outcome::Accepted)
+    }
+}
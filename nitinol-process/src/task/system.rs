@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+
+use crate::errors::{ChannelDropped, ProcessError};
+use crate::lifecycle::ProcessInfo;
+use crate::task::{Outcome, TaskApplier};
+use crate::{Context, Process};
+
+pub(crate) struct StopTask;
+
+#[async_trait]
+impl<T: Process> TaskApplier<T> for StopTask {
+    async fn apply(self: Box<Self>, _: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        ctx.shutdown.notify_one();
+        Ok(Outcome::Accepted)
+    }
+}
+
+pub(crate) struct InfoTask {
+    pub(crate) oneshot: oneshot::Sender<ProcessInfo>,
+}
+
+#[async_trait]
+impl<T: Process> TaskApplier<T> for InfoTask {
+    async fn apply(self: Box<Self>, _: &mut T, ctx: &mut Context) -> Result<Outcome, ProcessError> {
+        let info = ProcessInfo {
+            id: ctx.id.clone(),
+            sequence: ctx.sequence,
+            children: ctx.children().cloned().collect(),
+        };
+        self.oneshot
+            .send(info)
+            .map_err(|_| ChannelDropped)?;
+        Ok(Outcome::Accepted)
+    }
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use tokio::sync::Mutex;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::batch::{Journal, Staged};
+use nitinol_process::errors::{ProcessError, StageError};
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::task::EventApplicator;
+use nitinol_process::{Context, Process};
+
+pub struct Incremented;
+
+impl Event for Incremented {
+    const EVENT_TYPE: &'static str = "incremented";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Incremented)
+    }
+}
+
+pub struct Counter;
+
+impl Process for Counter {
+    fn aggregate_id(&self) -> EntityId {
+        "counter".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Incremented> for Counter {
+    async fn apply(&mut self, event: Incremented, ctx: &mut Context) {
+        assert!(ctx.is_batching());
+        ctx.stage(&event).unwrap();
+    }
+}
+
+type Flushed = Arc<Mutex<Vec<Vec<i64>>>>;
+
+#[derive(Clone, Default)]
+struct Recorder {
+    flushed: Flushed,
+    fail: bool,
+}
+
+#[async_trait]
+impl Journal for Recorder {
+    async fn flush(&self, id: &EntityId, events: Vec<Staged>) -> Result<(), Box<dyn Error + Sync + Send>> {
+        assert_eq!(id, &"counter".to_entity_id());
+        if self.fail {
+            return Err("journal unavailable".into());
+        }
+        self.flushed.lock().await.push(events.iter().map(|staged| staged.sequence).collect());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn events_are_flushed_in_batches() {
+    let journal = Recorder::default();
+    let system = ProcessManager::default().with_batch(4, journal.clone());
+    let refs = system.spawn(Counter, 0).await.unwrap();
+
+    let applied = join_all((0..10).map(|_| refs.apply(Incremented))).await;
+    assert!(applied.into_iter().all(|applied| applied.is_ok()));
+
+    let flushed = journal.flushed.lock().await;
+    assert!(flushed.iter().all(|batch| batch.len() <= 4));
+    assert!(flushed.len() < 10);
+    assert_eq!(flushed.concat(), (0..10).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn replies_wait_for_the_flush() {
+    let journal = Recorder::default();
+    let system = ProcessManager::default().with_batch(16, journal.clone());
+    let refs = system.spawn(Counter, 0).await.unwrap();
+
+    refs.apply(Incremented).await.unwrap();
+    assert_eq!(*journal.flushed.lock().await, vec![vec![0]]);
+}
+
+#[tokio::test]
+async fn failed_flush_is_reported_to_callers_and_stops_the_process() {
+    let journal = Recorder { fail: true, ..Recorder::default() };
+    let system = ProcessManager::default().with_batch(4, journal);
+    let refs = system.spawn(Counter, 0).await.unwrap();
+
+    let result = refs.apply(Incremented).await;
+    assert!(matches!(result, Err(ProcessError::BatchWriteFailed(_))));
+    assert!(matches!(refs.watch().await, Termination::Failed(_)));
+    assert!(system.find::<Counter>("counter").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn staging_outside_batch_mode_fails() {
+    let mut ctx = Context::new(0, Default::default());
+    assert!(matches!(ctx.stage(&Incremented), Err(StageError::NotBatching)));
+}
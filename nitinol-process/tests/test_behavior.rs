@@ -0,0 +1,86 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::behavior::{Behavior, BehaviorHandler};
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_process::task::Receive;
+use nitinol_process::{Context, Process};
+
+pub struct Work(&'static str);
+
+impl Message for Work {}
+
+pub struct Loaded;
+
+impl Message for Loaded {}
+
+pub struct Loader {
+    log: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[async_trait]
+impl Process for Loader {
+    fn aggregate_id(&self) -> EntityId {
+        "loader".to_entity_id()
+    }
+
+    async fn start(&self, ctx: &mut Context) {
+        ctx.stash();
+        ctx.become_behavior(Behavior::new().on::<Loaded>(Loading));
+    }
+}
+
+#[async_trait]
+impl Receive<Work> for Loader {
+    type Error = ();
+
+    async fn receive(&mut self, message: Work, _: &mut Context) -> Result<(), Self::Error> {
+        self.log.lock().await.push(message.0);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Receive<Loaded> for Loader {
+    type Error = ();
+
+    async fn receive(&mut self, _: Loaded, _: &mut Context) -> Result<(), Self::Error> {
+        self.log.lock().await.push("already loaded");
+        Ok(())
+    }
+}
+
+struct Loading;
+
+#[async_trait]
+impl BehaviorHandler<Loaded, Loader> for Loading {
+    async fn receive(&self, state: &mut Loader, _: Loaded, ctx: &mut Context) {
+        state.log.lock().await.push("loaded");
+        ctx.unbecome();
+        ctx.unstash_all();
+    }
+}
+
+// With the clock paused, a sleep only ends once the process is idle.
+#[tokio::test(start_paused = true)]
+async fn stashed_messages_are_applied_after_unstash() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let system = ProcessManager::default();
+    let refs = system.spawn(Loader { log: Arc::clone(&log) }, 0).await.unwrap();
+
+    refs.send(Work("first")).await.unwrap();
+    refs.send(Work("second")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(log.lock().await.is_empty());
+
+    refs.send(Loaded).await.unwrap();
+    refs.send(Work("third")).await.unwrap();
+    refs.send(Loaded).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert_eq!(*log.lock().await, vec!["loaded", "first", "second", "third", "already loaded"]);
+}
@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+use nitinol_core::command::{Command, CommandId};
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+
+pub struct Deposit {
+    request: Option<&'static str>,
+    amount: u64,
+}
+
+impl Command for Deposit {
+    fn command_id(&self) -> Option<CommandId> {
+        self.request.map(CommandId::new)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Deposited {
+    amount: u64,
+    balance: u64,
+}
+
+impl Event for Deposited {
+    const EVENT_TYPE: &'static str = "deposited";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok([self.amount.to_be_bytes(), self.balance.to_be_bytes()].concat())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let (amount, balance) = bytes.split_at(8);
+        Ok(Deposited {
+            amount: u64::from_be_bytes(amount.try_into().unwrap()),
+            balance: u64::from_be_bytes(balance.try_into().unwrap()),
+        })
+    }
+}
+
+pub struct Account {
+    balance: u64,
+    handled: usize,
+    persisted: Vec<Option<CommandId>>,
+}
+
+impl Account {
+    fn new() -> Self {
+        Self { balance: 0, handled: 0, persisted: Vec::new() }
+    }
+}
+
+impl Process for Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Deposit> for Account {
+    type Event = Deposited;
+    type Rejection = ();
+
+    async fn handle(&self, command: Deposit, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Ok(Deposited { amount: command.amount, balance: self.balance + command.amount })
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Deposited> for Account {
+    async fn apply(&mut self, event: Deposited, ctx: &mut Context) {
+        self.balance = event.balance;
+        self.handled += 1;
+        self.persisted.push(ctx.command_id().cloned());
+    }
+}
+
+pub struct Inspect;
+
+impl Command for Inspect {}
+
+#[async_trait]
+impl CommandHandler<Inspect> for Account {
+    type Event = Deposited;
+    type Rejection = (usize, Vec<Option<CommandId>>);
+
+    async fn handle(&self, _: Inspect, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Err((self.handled, self.persisted.clone()))
+    }
+}
+
+fn deposit(request: Option<&'static str>, amount: u64) -> Deposit {
+    Deposit { request, amount }
+}
+
+#[tokio::test]
+async fn duplicate_command_returns_original_event() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Account::new(), 0).await.unwrap();
+
+    let first = refs.handle(deposit(Some("req-1"), 10)).await.unwrap().unwrap();
+    refs.apply(first).await.unwrap();
+
+    let duplicate = refs.handle(deposit(Some("req-1"), 10)).await.unwrap().unwrap();
+    assert_eq!(duplicate, Deposited { amount: 10, balance: 10 });
+    refs.apply(duplicate).await.unwrap();
+
+    let other = refs.handle(deposit(Some("req-2"), 5)).await.unwrap().unwrap();
+    assert_eq!(other, Deposited { amount: 5, balance: 15 });
+    refs.apply(other).await.unwrap();
+
+    let (handled, persisted) = refs.handle(Inspect).await.unwrap().unwrap_err();
+    assert_eq!(handled, 2);
+    assert_eq!(persisted, vec![Some(CommandId::new("req-1")), Some(CommandId::new("req-2"))]);
+}
+
+#[tokio::test]
+async fn command_whose_event_was_not_applied_is_handled_again() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Account::new(), 0).await.unwrap();
+
+    // The caller fails before applying the event.
+    let _ = refs.handle(deposit(Some("req-1"), 10)).await.unwrap().unwrap();
+
+    let retried = refs.handle(deposit(Some("req-1"), 20)).await.unwrap().unwrap();
+    assert_eq!(retried, Deposited { amount: 20, balance: 20 });
+    refs.apply(retried).await.unwrap();
+
+    let (handled, persisted) = refs.handle(Inspect).await.unwrap().unwrap_err();
+    assert_eq!(handled, 1);
+    assert_eq!(persisted, vec![Some(CommandId::new("req-1"))]);
+}
+
+#[tokio::test]
+async fn entrusted_duplicate_is_applied_once() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Account::new(), 0).await.unwrap();
+
+    refs.entrust(deposit(Some("req-1"), 10)).await.unwrap();
+    refs.entrust(deposit(Some("req-1"), 10)).await.unwrap();
+    refs.entrust(deposit(None, 10)).await.unwrap();
+    refs.entrust(deposit(None, 10)).await.unwrap();
+
+    let (handled, persisted) = refs.handle(Inspect).await.unwrap().unwrap_err();
+    assert_eq!(handled, 3);
+    assert_eq!(persisted, vec![Some(CommandId::new("req-1")), None, None]);
+}
+
+#[tokio::test]
+async fn disabled_window_handles_every_command() {
+    let system = ProcessManager::default().with_dedup_window(0);
+    let refs = system.spawn(Account::new(), 0).await.unwrap();
+
+    refs.entrust(deposit(Some("req-1"), 10)).await.unwrap();
+    refs.entrust(deposit(Some("req-1"), 10)).await.unwrap();
+
+    let (handled, _) = refs.handle(Inspect).await.unwrap().unwrap_err();
+    assert_eq!(handled, 2);
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::errors::{Intercepted, ProcessError};
+use nitinol_process::interceptor::{Interceptor, TaskInfo};
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::metrics::TaskKind;
+use nitinol_process::task::{CommandHandler, EventApplicator, Outcome};
+use nitinol_process::{Context, Process};
+
+pub enum Withdraw {
+    Amount(u64),
+    Overdraw,
+}
+
+impl Command for Withdraw {}
+
+pub struct Withdrawn(u64);
+
+impl Event for Withdrawn {
+    const EVENT_TYPE: &'static str = "withdrawn";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Withdrawn(u64::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+pub struct Account {
+    balance: u64,
+}
+
+impl Process for Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Withdraw> for Account {
+    type Event = Withdrawn;
+    type Rejection = ();
+
+    async fn handle(&self, command: Withdraw, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        match command {
+            Withdraw::Amount(amount) if amount <= self.balance => Ok(Withdrawn(amount)),
+            _ => Err(()),
+        }
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Withdrawn> for Account {
+    async fn apply(&mut self, event: Withdrawn, _: &mut Context) {
+        self.balance -= event.0;
+    }
+}
+
+type Observed = Arc<Mutex<Vec<(TaskKind, Outcome)>>>;
+
+/// Lets at most `limit` tasks through and records the outcome of each.
+struct Limit {
+    limit: usize,
+    seen: Arc<Mutex<usize>>,
+    observed: Observed,
+}
+
+#[async_trait]
+impl Interceptor for Limit {
+    async fn before(&self, task: &TaskInfo, ctx: &Context) -> Result<(), Intercepted> {
+        assert_eq!(ctx.id(), &"account".to_entity_id());
+        let mut seen = self.seen.lock().await;
+        if *seen == self.limit {
+            return Err(Intercepted(format!("limit reached for {}", task.name)));
+        }
+        *seen += 1;
+        Ok(())
+    }
+
+    async fn after(&self, task: &TaskInfo, _: &Context, result: &Result<Outcome, ProcessError>, _: Duration) {
+        self.observed.lock().await.push((task.kind, *result.as_ref().unwrap()));
+    }
+}
+
+fn limit(limit: usize) -> (Limit, Observed) {
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let interceptor = Limit {
+        limit,
+        seen: Arc::new(Mutex::new(0)),
+        observed: Arc::clone(&observed),
+    };
+    (interceptor, observed)
+}
+
+#[tokio::test]
+async fn interceptor_observes_outcomes() {
+    let (interceptor, observed) = limit(usize::MAX);
+    let system = ProcessManager::default().with_interceptor(interceptor);
+    let refs = system.spawn(Account { balance: 10 }, 0).await.unwrap();
+
+    let event = refs.handle(Withdraw::Amount(3)).await.unwrap().unwrap();
+    refs.apply(event).await.unwrap();
+    assert!(refs.handle(Withdraw::Overdraw).await.unwrap().is_err());
+
+    assert_eq!(*observed.lock().await, vec![
+        (TaskKind::Command, Outcome::Accepted),
+        (TaskKind::Event, Outcome::Accepted),
+        (TaskKind::Command, Outcome::Rejected),
+    ]);
+}
+
+#[tokio::test]
+async fn interceptor_short_circuits_without_stopping_process() {
+    let (interceptor, observed) = limit(1);
+    let system = ProcessManager::default().with_interceptor(interceptor);
+    let refs = system.spawn(Account { balance: 10 }, 0).await.unwrap();
+
+    assert!(refs.handle(Withdraw::Amount(3)).await.unwrap().is_ok());
+    let result = refs.handle(Withdraw::Amount(3)).await;
+    assert!(matches!(result, Err(ProcessError::Intercepted(ref e)) if e.0.contains("Withdraw")));
+
+    assert_eq!(observed.lock().await.len(), 1);
+    assert_eq!(refs.termination(), None);
+    assert!(system.find::<Account>("account").await.unwrap().is_some());
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::mailbox::Priority;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_process::task::Receive;
+use nitinol_process::{Context, Process};
+
+pub struct Work(&'static str);
+
+impl Message for Work {}
+
+pub struct Worker {
+    done: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Process for Worker {
+    fn aggregate_id(&self) -> EntityId {
+        "worker".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl Receive<Work> for Worker {
+    type Error = ();
+
+    async fn receive(&mut self, message: Work, _: &mut Context) -> Result<(), Self::Error> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.done.lock().await.push(message.0);
+        Ok(())
+    }
+}
+
+fn worker() -> (Worker, Arc<Mutex<Vec<&'static str>>>) {
+    let done = Arc::new(Mutex::new(Vec::new()));
+    (Worker { done: Arc::clone(&done) }, done)
+}
+
+// With the clock paused, a sleep only ends once every task is idle or sleeping.
+#[tokio::test(start_paused = true)]
+async fn high_priority_messages_overtake_normal_ones() {
+    let system = ProcessManager::default();
+    let (worker, done) = worker();
+    let refs = system.spawn(worker, 0).await.unwrap();
+
+    refs.send(Work("first")).await.unwrap();
+    // `first` is being worked on.
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    refs.send(Work("normal")).await.unwrap();
+    refs.send_with(Work("urgent"), Priority::High).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(*done.lock().await, vec!["first", "urgent", "normal"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn info_and_stop_skip_the_queue() {
+    let system = ProcessManager::default();
+    let (worker, done) = worker();
+    let refs = system.spawn(worker, 0).await.unwrap();
+
+    for _ in 0..10 {
+        refs.send(Work("queued")).await.unwrap();
+    }
+
+    let info = refs.info().await.unwrap();
+    assert_eq!(info.id, "worker".to_entity_id());
+    assert!(done.lock().await.len() < 2);
+
+    refs.stop().await.unwrap();
+    assert_eq!(refs.watch().await, Termination::Stopped);
+    assert!(done.lock().await.len() < 3);
+    assert!(system.find::<Worker>("worker").await.unwrap().is_none());
+}
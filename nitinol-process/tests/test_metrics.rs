@@ -0,0 +1,126 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::metrics::{Metrics, TaskKind};
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+
+pub enum Order {
+    Accept,
+    Refuse,
+    Cancel,
+}
+
+impl Command for Order {}
+
+pub enum OrderEvent {
+    Accepted,
+    Cancelled,
+}
+
+impl Event for OrderEvent {
+    const EVENT_TYPE: &'static str = "order-event";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(OrderEvent::Accepted)
+    }
+}
+
+pub struct Aggregate;
+
+impl Process for Aggregate {
+    fn aggregate_id(&self) -> EntityId {
+        "aggregate".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Order> for Aggregate {
+    type Event = OrderEvent;
+    type Rejection = ();
+
+    async fn handle(&self, command: Order, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        match command {
+            Order::Accept => Ok(OrderEvent::Accepted),
+            Order::Refuse => Err(()),
+            Order::Cancel => Ok(OrderEvent::Cancelled),
+        }
+    }
+}
+
+#[async_trait]
+impl EventApplicator<OrderEvent> for Aggregate {
+    async fn apply(&mut self, event: OrderEvent, ctx: &mut Context) {
+        if let OrderEvent::Cancelled = event {
+            ctx.poison().await;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Recorded {
+    processed: Vec<TaskKind>,
+    rejected: Vec<TaskKind>,
+    dropped: Vec<TaskKind>,
+    terminated: Option<Termination>,
+}
+
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<Recorded>>);
+
+impl Metrics for Recorder {
+    fn task_processed(&self, id: &EntityId, kind: TaskKind, _: std::time::Duration) {
+        assert_eq!(id, &"aggregate".to_entity_id());
+        self.0.lock().unwrap().processed.push(kind);
+    }
+
+    fn rejected(&self, _: &EntityId, kind: TaskKind) {
+        self.0.lock().unwrap().rejected.push(kind);
+    }
+
+    fn dropped(&self, _: &EntityId, kind: TaskKind) {
+        self.0.lock().unwrap().dropped.push(kind);
+    }
+
+    fn lifetime(&self, _: &EntityId, _: std::time::Duration, reason: &Termination) {
+        self.0.lock().unwrap().terminated = Some(reason.clone());
+    }
+}
+
+#[tokio::test]
+async fn metrics_observe_each_task() {
+    let recorder = Recorder::default();
+    let system = ProcessManager::default().with_metrics(recorder.clone());
+    let refs = system.spawn(Aggregate, 0).await.unwrap();
+
+    let event = refs.handle(Order::Accept).await.unwrap().unwrap();
+    refs.apply(event).await.unwrap();
+    assert!(refs.handle(Order::Refuse).await.unwrap().is_err());
+    refs.entrust(Order::Refuse).await.unwrap();
+    refs.entrust(Order::Cancel).await.unwrap();
+    // Still queued when `Cancel` poisons the process.
+    refs.entrust(Order::Accept).await.unwrap();
+    refs.watch().await;
+
+    let recorded = recorder.0.lock().unwrap();
+    assert_eq!(recorded.processed, vec![
+        TaskKind::Command,
+        TaskKind::Event,
+        TaskKind::Command,
+        TaskKind::Entrust,
+        TaskKind::Entrust,
+    ]);
+    assert_eq!(recorded.rejected, vec![TaskKind::Command, TaskKind::Entrust]);
+    assert_eq!(recorded.dropped, vec![TaskKind::Entrust]);
+    assert_eq!(recorded.terminated, Some(Termination::Poisoned));
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::errors::ProcessError;
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::supervisor::FailurePolicy;
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+
+pub enum Order {
+    Place,
+    Explode,
+}
+
+impl Command for Order {}
+
+pub struct Placed;
+
+impl Event for Placed {
+    const EVENT_TYPE: &'static str = "placed";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Placed)
+    }
+}
+
+pub struct Aggregate;
+
+impl Process for Aggregate {
+    fn aggregate_id(&self) -> EntityId {
+        "aggregate".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Order> for Aggregate {
+    type Event = Placed;
+    type Rejection = ();
+
+    async fn handle(&self, command: Order, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        match command {
+            Order::Place => Ok(Placed),
+            Order::Explode => panic!("boom"),
+        }
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Placed> for Aggregate {
+    async fn apply(&mut self, _: Placed, _: &mut Context) {}
+}
+
+#[tokio::test]
+async fn panic_is_returned_to_caller_and_process_is_deregistered() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Aggregate, 0).await.unwrap();
+
+    let result = refs.handle(Order::Explode).await;
+    assert!(matches!(result, Err(ProcessError::Panicked(ref panicked)) if panicked.0 == "boom"));
+
+    assert_eq!(refs.watch().await, Termination::Panicked("boom".to_string()));
+    assert!(system.find::<Aggregate>("aggregate").await.unwrap().is_none());
+    assert!(matches!(refs.handle(Order::Place).await, Err(ProcessError::ChannelDropped(_))));
+}
+
+#[tokio::test]
+async fn continue_policy_keeps_process_alive() {
+    let system = ProcessManager::default().with_policy(FailurePolicy::Continue);
+    let refs = system.spawn(Aggregate, 0).await.unwrap();
+
+    assert!(matches!(refs.handle(Order::Explode).await, Err(ProcessError::Panicked(_))));
+    refs.entrust(Order::Explode).await.unwrap();
+
+    let event = refs.handle(Order::Place).await.unwrap().unwrap();
+    // Applied after the entrusted command that panicked.
+    refs.apply(event).await.unwrap();
+
+    assert_eq!(refs.termination(), None);
+    assert!(system.find::<Aggregate>("aggregate").await.unwrap().is_some());
+}
@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::errors::ProcessError;
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+
+pub enum AccountCommand {
+    Deposit,
+    Close,
+}
+
+impl Command for AccountCommand {}
+
+pub enum AccountEvent {
+    Deposited,
+    Closed,
+}
+
+impl Event for AccountEvent {
+    const EVENT_TYPE: &'static str = "account-event";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(AccountEvent::Deposited)
+    }
+}
+
+pub struct Account {
+    deposits: usize,
+}
+
+impl Process for Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<AccountCommand> for Account {
+    type Event = AccountEvent;
+    type Rejection = ();
+
+    async fn handle(&self, command: AccountCommand, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Ok(match command {
+            AccountCommand::Deposit => AccountEvent::Deposited,
+            AccountCommand::Close => AccountEvent::Closed,
+        })
+    }
+}
+
+#[async_trait]
+impl EventApplicator<AccountEvent> for Account {
+    async fn apply(&mut self, event: AccountEvent, ctx: &mut Context) {
+        match event {
+            AccountEvent::Deposited => self.deposits += 1,
+            AccountEvent::Closed => ctx.poison().await,
+        }
+    }
+}
+
+#[tokio::test]
+async fn poison_stops_the_mailbox_loop() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Account { deposits: 0 }, 0).await.unwrap();
+
+    refs.entrust(AccountCommand::Deposit).await.unwrap();
+    refs.entrust(AccountCommand::Close).await.unwrap();
+    let queued = refs.handle(AccountCommand::Deposit).await;
+
+    assert!(matches!(queued, Err(ProcessError::Poisoned(_))));
+    assert_eq!(refs.watch().await, Termination::Poisoned);
+    assert_eq!(refs.termination(), Some(Termination::Poisoned));
+    assert!(system.find::<Account>("account").await.unwrap().is_none());
+    assert!(matches!(refs.entrust(AccountCommand::Deposit).await, Err(ProcessError::Poisoned(_))));
+}
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_process::task::{EventApplicator, Query, Receive};
+use nitinol_process::{Context, Process};
+
+pub struct Incremented;
+
+impl Event for Incremented {
+    const EVENT_TYPE: &'static str = "incremented";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Incremented)
+    }
+}
+
+pub struct Nap;
+
+impl Message for Nap {}
+
+pub struct Bump;
+
+impl Message for Bump {}
+
+pub struct Current;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Count(u64);
+
+pub struct Counter {
+    count: u64,
+}
+
+impl Process for Counter {
+    fn aggregate_id(&self) -> EntityId {
+        "counter".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Incremented> for Counter {
+    async fn apply(&mut self, _: Incremented, ctx: &mut Context) {
+        self.count += 1;
+        ctx.publish(Count(self.count));
+    }
+}
+
+#[async_trait]
+impl Receive<Nap> for Counter {
+    type Error = ();
+
+    async fn receive(&mut self, _: Nap, _: &mut Context) -> Result<(), Self::Error> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Receive<Bump> for Counter {
+    type Error = ();
+
+    async fn receive(&mut self, _: Bump, _: &mut Context) -> Result<(), Self::Error> {
+        self.count += 1;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Query<Current> for Counter {
+    type Output = u64;
+
+    async fn query(&self, _: Current, _: &Context) -> Self::Output {
+        self.count
+    }
+}
+
+#[tokio::test]
+async fn query_reads_current_state() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Counter { count: 0 }, 0).await.unwrap();
+
+    assert_eq!(refs.query(Current).await.unwrap(), 0);
+    refs.apply(Incremented).await.unwrap();
+    refs.apply(Incremented).await.unwrap();
+    assert_eq!(refs.query(Current).await.unwrap(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn query_observes_tasks_sent_before_it() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Counter { count: 0 }, 0).await.unwrap();
+
+    refs.send(Nap).await.unwrap();
+    refs.send(Bump).await.unwrap();
+    assert_eq!(refs.query(Current).await.unwrap(), 1);
+}
+
+// With the clock paused, the process is still napping when the test wakes up.
+#[tokio::test(start_paused = true)]
+async fn snapshot_is_readable_while_process_is_busy() {
+    let system = ProcessManager::default();
+    let refs = system.spawn(Counter { count: 0 }, 0).await.unwrap();
+    assert!(refs.snapshot::<Count>().is_none());
+
+    refs.apply(Incremented).await.unwrap();
+    let mut snapshot = refs.snapshot::<Count>().unwrap();
+    assert_eq!(*snapshot.borrow(), Count(1));
+
+    refs.send(Nap).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let read = tokio::time::timeout(Duration::from_millis(10), async { snapshot.borrow().clone() }).await;
+    assert_eq!(read.unwrap(), Count(1));
+
+    refs.apply(Incremented).await.unwrap();
+    snapshot.changed().await.unwrap();
+    assert_eq!(*snapshot.borrow(), Count(2));
+}
@@ -0,0 +1,136 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use tokio::sync::Barrier;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::EntityId;
+use nitinol_process::errors::RouteError;
+use nitinol_process::factory::{Factory, Replayed};
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::task::CommandHandler;
+use nitinol_process::{Context, Process};
+
+pub struct Greet;
+
+impl Command for Greet {}
+
+#[derive(Debug, PartialEq)]
+pub struct Greeted(String);
+
+impl Event for Greeted {
+    const EVENT_TYPE: &'static str = "greeted";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.as_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Greeted(String::from_utf8_lossy(bytes).into_owned()))
+    }
+}
+
+pub struct User {
+    id: EntityId,
+}
+
+impl Process for User {
+    fn aggregate_id(&self) -> EntityId {
+        self.id.clone()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Greet> for User {
+    type Event = Greeted;
+    type Rejection = ();
+
+    async fn handle(&self, _: Greet, ctx: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Ok(Greeted(format!("{}@{}", self.id, ctx.sequence())))
+    }
+}
+
+#[tokio::test]
+async fn route_spawns_missing_process_once() {
+    let created = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&created);
+    let system = ProcessManager::default().with_factory(move |id: &EntityId| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        User { id: id.clone() }
+    });
+
+    assert_eq!(system.route::<User, _>("alice", Greet).await.unwrap(), Ok(Greeted("alice@0".to_string())));
+    assert_eq!(system.route::<User, _>("alice", Greet).await.unwrap(), Ok(Greeted("alice@0".to_string())));
+    assert_eq!(system.route::<User, _>("bob", Greet).await.unwrap(), Ok(Greeted("bob@0".to_string())));
+
+    assert_eq!(created.load(Ordering::SeqCst), 2);
+    assert!(system.find::<User>("alice").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn route_without_factory_fails() {
+    let system = ProcessManager::default();
+    let result = system.route::<User, _>("alice", Greet).await;
+    assert!(matches!(result, Err(RouteError::NoFactory(_))));
+}
+
+pub struct Session {
+    id: EntityId,
+    started: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Process for Session {
+    fn aggregate_id(&self) -> EntityId {
+        self.id.clone()
+    }
+
+    async fn start(&self, _: &mut Context) {
+        self.started.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Greet> for Session {
+    type Event = Greeted;
+    type Rejection = ();
+
+    async fn handle(&self, _: Greet, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Ok(Greeted(self.id.to_string()))
+    }
+}
+
+/// Holds every creation back until all concurrent lookups have missed the registry.
+struct GatedFactory {
+    started: Arc<AtomicUsize>,
+    gate: Arc<Barrier>,
+}
+
+#[async_trait]
+impl Factory<Session> for GatedFactory {
+    async fn create(&self, id: &EntityId) -> Result<Replayed<Session>, Box<dyn Error + Sync + Send>> {
+        self.gate.wait().await;
+        Ok(Replayed::new(Session { id: id.clone(), started: Arc::clone(&self.started) }, 0))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_locate_spawns_one_process() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let factory = GatedFactory { started: Arc::clone(&started), gate: Arc::new(Barrier::new(16)) };
+    let system = ProcessManager::default().with_factory(factory);
+
+    let located = join_all((0..16).map(|_| {
+        let system = system.clone();
+        tokio::spawn(async move { system.locate::<Session>("alice").await })
+    })).await;
+    for refs in located {
+        assert_eq!(refs.unwrap().unwrap().handle(Greet).await.unwrap(), Ok(Greeted("alice".to_string())));
+    }
+
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::errors::RouteError;
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_process::router::Strategy;
+use nitinol_process::task::{Query, Receive};
+use nitinol_process::{Context, Process};
+
+pub struct Whoami;
+
+pub struct Sleep;
+
+impl Message for Sleep {}
+
+pub struct Crash;
+
+impl Message for Crash {}
+
+pub struct Worker {
+    id: EntityId,
+}
+
+impl Process for Worker {
+    fn aggregate_id(&self) -> EntityId {
+        self.id.clone()
+    }
+}
+
+#[async_trait]
+impl Query<Whoami> for Worker {
+    type Output = EntityId;
+
+    async fn query(&self, _: Whoami, ctx: &Context) -> Self::Output {
+        ctx.id().clone()
+    }
+}
+
+#[async_trait]
+impl Receive<Sleep> for Worker {
+    type Error = ();
+
+    async fn receive(&mut self, _: Sleep, _: &mut Context) -> Result<(), Self::Error> {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Receive<Crash> for Worker {
+    type Error = &'static str;
+
+    async fn receive(&mut self, _: Crash, _: &mut Context) -> Result<(), Self::Error> {
+        Err("crashed on purpose")
+    }
+}
+
+fn worker(id: &EntityId) -> Worker {
+    Worker { id: id.clone() }
+}
+
+#[tokio::test]
+async fn round_robin_visits_every_routee() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 3, Strategy::RoundRobin, worker).await.unwrap();
+
+    let mut visited = Vec::new();
+    for _ in 0..6 {
+        visited.push(router.query(Whoami).await.unwrap());
+    }
+
+    let expected = ["workers/0", "workers/1", "workers/2"].map(|id| id.to_entity_id());
+    assert_eq!(visited, [expected.clone(), expected].concat());
+    assert!(system.find::<Worker>("workers/2").await.unwrap().is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn smallest_mailbox_avoids_busy_routees() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 2, Strategy::SmallestMailbox, worker).await.unwrap();
+
+    let busy = system.find::<Worker>("workers/0").await.unwrap().unwrap();
+    for _ in 0..3 {
+        busy.send(Sleep).await.unwrap();
+    }
+
+    assert_eq!(router.routee().await.unwrap().id(), &"workers/1".to_entity_id());
+}
+
+#[tokio::test]
+async fn consistent_hash_is_stable_per_key() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 4, Strategy::ConsistentHash, worker).await.unwrap();
+
+    let mut owners = HashSet::new();
+    for key in 0..32 {
+        let first = router.routee_for(&key).await.unwrap();
+        let again = router.routee_for(&key).await.unwrap();
+        assert_eq!(first.id(), again.id());
+        owners.insert(first.id().clone());
+    }
+    assert!(owners.len() > 1);
+    assert!(matches!(router.query(Whoami).await, Err(RouteError::KeyRequired(_))));
+}
+
+#[tokio::test]
+async fn resize_spawns_and_stops_routees() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 1, Strategy::Random, worker).await.unwrap();
+
+    router.resize(3).await.unwrap();
+    assert_eq!(router.size().await, 3);
+    assert!(system.find::<Worker>("workers/2").await.unwrap().is_some());
+
+    router.resize(0).await.unwrap();
+    assert!(system.find::<Worker>("workers/0").await.unwrap().is_none());
+    assert!(matches!(router.query(Whoami).await, Err(RouteError::NoRoutee(_))));
+
+    router.resize(2).await.unwrap();
+    assert_eq!(router.size().await, 2);
+}
+
+/// Wait until the tasks watching the routees have caught up with `done`.
+async fn eventually<F: std::future::Future<Output = bool>>(mut done: impl FnMut() -> F) {
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while !done().await {
+            tokio::task::yield_now().await;
+        }
+    }).await.unwrap();
+}
+
+#[tokio::test]
+async fn failed_routee_is_respawned() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 2, Strategy::RoundRobin, worker).await.unwrap();
+
+    let failed = system.find::<Worker>("workers/0").await.unwrap().unwrap();
+    failed.send(Crash).await.unwrap();
+    assert!(matches!(failed.watch().await, Termination::Failed(_)));
+
+    eventually(|| async { system.find::<Worker>("workers/0").await.unwrap().is_some() }).await;
+    assert_eq!(router.size().await, 2);
+    let mut visited = HashSet::new();
+    for _ in 0..2 {
+        visited.insert(router.query(Whoami).await.unwrap());
+    }
+    assert_eq!(visited, HashSet::from(["workers/0".to_entity_id(), "workers/1".to_entity_id()]));
+}
+
+#[tokio::test]
+async fn stopped_routee_leaves_the_pool() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 2, Strategy::RoundRobin, worker).await.unwrap();
+
+    let stopped = system.find::<Worker>("workers/0").await.unwrap().unwrap();
+    stopped.stop().await.unwrap();
+    assert_eq!(stopped.watch().await, Termination::Stopped);
+
+    eventually(|| async { router.size().await == 1 }).await;
+    for _ in 0..2 {
+        assert_eq!(router.query(Whoami).await.unwrap(), "workers/1".to_entity_id());
+    }
+
+    router.resize(2).await.unwrap();
+    assert!(system.find::<Worker>("workers/0").await.unwrap().is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn routing_continues_while_resizing() {
+    let system = ProcessManager::default();
+    let router = system.pool("workers", 2, Strategy::RoundRobin, worker).await.unwrap();
+
+    let busy = system.find::<Worker>("workers/1").await.unwrap().unwrap();
+    busy.send(Sleep).await.unwrap();
+    let shrinking = tokio::spawn({
+        let router = router.clone();
+        async move { router.resize(1).await }
+    });
+    eventually(|| async { router.size().await == 1 }).await;
+
+    // `workers/1` is still finishing its `Sleep` before it stops.
+    assert_eq!(router.query(Whoami).await.unwrap(), "workers/0".to_entity_id());
+    assert!(!shrinking.is_finished());
+    shrinking.await.unwrap().unwrap();
+    assert!(system.find::<Worker>("workers/1").await.unwrap().is_none());
+}
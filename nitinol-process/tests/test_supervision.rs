@@ -0,0 +1,261 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::supervisor::{Directive, FailurePolicy};
+use nitinol_process::message::Message;
+use nitinol_process::task::{CommandHandler, Receive};
+use nitinol_process::{Context, Process, Receptor};
+
+pub struct Ping;
+
+impl Command for Ping {}
+
+pub struct Fail;
+
+impl Message for Fail {}
+
+pub struct Done;
+
+impl Event for Done {
+    const EVENT_TYPE: &'static str = "done";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Done)
+    }
+}
+
+type Recorded = Arc<Mutex<Vec<EntityId>>>;
+
+pub struct Parent {
+    directive: Directive,
+    supervised: Recorded,
+    terminated: Recorded,
+}
+
+#[async_trait]
+impl Process for Parent {
+    fn aggregate_id(&self) -> EntityId {
+        "parent".to_entity_id()
+    }
+
+    async fn start(&self, ctx: &mut Context) {
+        ctx.spawn_child(Child, 0).await.unwrap();
+    }
+
+    async fn supervise(&mut self, child: &EntityId, _: &str, _: &mut Context) -> Directive {
+        self.supervised.lock().await.push(child.clone());
+        self.directive
+    }
+
+    async fn terminated(&mut self, child: &EntityId, _: &Termination, ctx: &mut Context) {
+        assert_eq!(ctx.children().count(), 0);
+        self.terminated.lock().await.push(child.clone());
+    }
+}
+
+pub struct Child;
+
+impl Process for Child {
+    fn aggregate_id(&self) -> EntityId {
+        "child".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Ping> for Child {
+    type Event = Done;
+    type Rejection = ();
+
+    async fn handle(&self, _: Ping, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Ok(Done)
+    }
+}
+
+#[async_trait]
+impl Receive<Fail> for Child {
+    type Error = &'static str;
+
+    async fn receive(&mut self, _: Fail, _: &mut Context) -> Result<(), Self::Error> {
+        Err("failed on purpose")
+    }
+}
+
+fn parent(directive: Directive) -> (Parent, Recorded, Recorded) {
+    let supervised = Arc::new(Mutex::new(Vec::new()));
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+    let parent = Parent {
+        directive,
+        supervised: Arc::clone(&supervised),
+        terminated: Arc::clone(&terminated),
+    };
+    (parent, supervised, terminated)
+}
+
+/// Spawn `parent`, returning once it has spawned its child.
+async fn spawn(system: &ProcessManager, parent: Parent) -> (Receptor<Parent>, Receptor<Child>) {
+    let parent = system.spawn(parent, 0).await.unwrap();
+    // Answered after `start`.
+    parent.info().await.unwrap();
+    let child = system.find::<Child>("parent/child").await.unwrap().unwrap();
+    (parent, child)
+}
+
+/// Make `child` fail to receive a message, returning once its parent has decided what to do with it.
+async fn fail(child: &Receptor<Child>) {
+    child.send(Fail).await.unwrap();
+    // Handled once the child is resumed, discarded once it is stopped.
+    let _ = child.handle(Ping).await;
+}
+
+#[tokio::test]
+async fn child_is_registered_under_parent_path() {
+    let system = ProcessManager::default();
+    let (parent, _, _) = parent(Directive::Stop);
+    spawn(&system, parent).await;
+
+    assert!(system.find::<Child>("parent/child").await.unwrap().is_some());
+    assert!(system.find::<Child>("child").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn parent_is_notified_when_child_stops() {
+    let system = ProcessManager::default();
+    let (parent, supervised, terminated) = parent(Directive::Stop);
+    let (parent, child) = spawn(&system, parent).await;
+
+    fail(&child).await;
+    child.watch().await;
+    // Answered after the termination of the child was handled.
+    parent.info().await.unwrap();
+
+    let child = "parent/child".to_entity_id();
+    assert_eq!(*supervised.lock().await, vec![child.clone()]);
+    assert_eq!(*terminated.lock().await, vec![child]);
+    assert!(system.find::<Child>("parent/child").await.unwrap().is_none());
+    assert!(system.find::<Parent>("parent").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn resumed_child_keeps_running() {
+    let system = ProcessManager::default();
+    let (parent, supervised, terminated) = parent(Directive::Resume);
+    let (_parent, child) = spawn(&system, parent).await;
+
+    fail(&child).await;
+
+    assert_eq!(supervised.lock().await.len(), 1);
+    assert!(terminated.lock().await.is_empty());
+    assert_eq!(child.termination(), None);
+    assert!(system.find::<Child>("parent/child").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn escalation_fails_parent() {
+    let system = ProcessManager::default();
+    let (parent, _, _) = parent(Directive::Escalate);
+    let (parent, child) = spawn(&system, parent).await;
+
+    fail(&child).await;
+
+    assert!(matches!(parent.watch().await, Termination::Failed(reason) if reason.contains("escalated")));
+    assert!(system.find::<Child>("parent/child").await.unwrap().is_none());
+    assert!(system.find::<Parent>("parent").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn escalation_follows_the_failure_policy_of_parent() {
+    let system = ProcessManager::default().with_policy(FailurePolicy::Continue);
+    let (parent, _, _) = parent(Directive::Escalate);
+    let (parent, child) = spawn(&system, parent).await;
+
+    fail(&child).await;
+    child.watch().await;
+    parent.info().await.unwrap();
+
+    assert!(system.find::<Child>("parent/child").await.unwrap().is_none());
+    assert_eq!(parent.termination(), None);
+}
+
+pub struct Grandparent {
+    parent: std::sync::Mutex<Option<Parent>>,
+    supervised: Recorded,
+}
+
+#[async_trait]
+impl Process for Grandparent {
+    fn aggregate_id(&self) -> EntityId {
+        "grandparent".to_entity_id()
+    }
+
+    async fn start(&self, ctx: &mut Context) {
+        let parent = self.parent.lock().unwrap().take().unwrap();
+        ctx.spawn_child(parent, 0).await.unwrap();
+    }
+
+    async fn supervise(&mut self, child: &EntityId, _: &str, _: &mut Context) -> Directive {
+        self.supervised.lock().await.push(child.clone());
+        Directive::Resume
+    }
+}
+
+#[tokio::test]
+async fn escalation_is_supervised_by_grandparent() {
+    let system = ProcessManager::default();
+    let (parent, _, _) = parent(Directive::Escalate);
+    let supervised = Arc::new(Mutex::new(Vec::new()));
+    let grandparent = Grandparent { parent: std::sync::Mutex::new(Some(parent)), supervised: Arc::clone(&supervised) };
+    let grandparent = system.spawn(grandparent, 0).await.unwrap();
+    grandparent.info().await.unwrap();
+    let parent = system.find::<Parent>("grandparent/parent").await.unwrap().unwrap();
+    parent.info().await.unwrap();
+
+    let child = system.find::<Child>("grandparent/parent/child").await.unwrap().unwrap();
+    fail(&child).await;
+    // Answered once the parent was resumed by the grandparent.
+    parent.info().await.unwrap();
+
+    assert_eq!(*supervised.lock().await, vec!["grandparent/parent".to_entity_id()]);
+    assert_eq!(parent.termination(), None);
+}
+
+#[tokio::test]
+async fn receptor_watch_reports_failure() {
+    let system = ProcessManager::default();
+    let (parent, _, _) = parent(Directive::Stop);
+    let (parent, child) = spawn(&system, parent).await;
+
+    let watch = child.watch();
+    assert_eq!(child.termination(), None);
+
+    fail(&child).await;
+
+    assert!(matches!(watch.await, Termination::Failed(_)));
+    assert!(matches!(child.termination(), Some(Termination::Failed(_))));
+    assert_eq!(parent.termination(), None);
+}
+
+#[tokio::test]
+async fn abandoned_reply_is_not_a_failure() {
+    let system = ProcessManager::default();
+    let (parent, supervised, _) = parent(Directive::Stop);
+    let (_parent, child) = spawn(&system, parent).await;
+
+    // Gives up right after sending the command.
+    let _ = tokio::time::timeout(Duration::ZERO, child.handle(Ping)).await;
+    child.handle(Ping).await.unwrap().unwrap();
+
+    assert!(supervised.lock().await.is_empty());
+    assert_eq!(child.termination(), None);
+}
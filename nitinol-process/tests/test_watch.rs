@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::lifecycle::Termination;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_process::task::Receive;
+use nitinol_process::{Context, Process};
+
+pub struct Fail;
+
+impl Message for Fail {}
+
+pub struct Peer;
+
+impl Process for Peer {
+    fn aggregate_id(&self) -> EntityId {
+        "peer".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl Receive<Fail> for Peer {
+    type Error = &'static str;
+
+    async fn receive(&mut self, _: Fail, _: &mut Context) -> Result<(), Self::Error> {
+        Err("failed on purpose")
+    }
+}
+
+pub struct Watcher {
+    observed: mpsc::UnboundedSender<(EntityId, Termination)>,
+}
+
+#[async_trait]
+impl Process for Watcher {
+    fn aggregate_id(&self) -> EntityId {
+        "watcher".to_entity_id()
+    }
+
+    async fn start(&self, ctx: &mut Context) {
+        let peer = ctx.find::<Peer>(&"peer".to_entity_id()).await.unwrap();
+        ctx.watch(&peer);
+    }
+
+    async fn terminated(&mut self, id: &EntityId, reason: &Termination, _: &mut Context) {
+        let _ = self.observed.send((id.clone(), reason.clone()));
+    }
+}
+
+#[tokio::test]
+async fn watcher_is_notified_when_peer_terminates() {
+    let system = ProcessManager::default();
+    let peer = system.spawn(Peer, 0).await.unwrap();
+
+    let (observed, mut notified) = mpsc::unbounded_channel();
+    let watcher = system.spawn(Watcher { observed }, 0).await.unwrap();
+    // Answered after `start`, once the peer is watched.
+    watcher.info().await.unwrap();
+
+    peer.send(Fail).await.unwrap();
+    let reason = peer.watch().await;
+
+    assert!(matches!(reason, Termination::Failed(_)));
+    assert_eq!(notified.recv().await, Some(("peer".to_entity_id(), reason)));
+    assert_eq!(watcher.termination(), None);
+}
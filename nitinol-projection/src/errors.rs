@@ -1,9 +1,14 @@
+use nitinol_protocol::errors::ProtocolError;
 use nitinol_resolver::errors::ResolveError;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ProjectionError {
     #[error("Failed to read protocol. {0}")]
-    Protocol(#[from] nitinol_protocol::errors::ProtocolError),
+    Protocol(#[source] ProtocolError),
+
+    /// The key encrypting the event was shredded, it will never be readable again.
+    #[error("Event {seq} of {id} was shredded.")]
+    Shredded { id: String, seq: i64 },
 
     #[error(transparent)]
     NotCompatible(#[from] NotCompatible),
@@ -30,6 +35,15 @@ pub struct NotCompatible {
     pub key: String,
 }
 
+impl From<ProtocolError> for ProjectionError {
+    fn from(value: ProtocolError) -> Self {
+        match value {
+            ProtocolError::Shredded { id, seq } => Self::Shredded { id, seq },
+            e => Self::Protocol(e),
+        }
+    }
+}
+
 impl From<ResolveError> for ProjectionError {
     fn from(value: ResolveError) -> Self {
         match value {
@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+use nitinol_process::factory::{Factory, Replayed};
+use nitinol_process::Process;
+use nitinol_resolver::mapping::ResolveMapping;
+
+use crate::projector::EventProjector;
+
+/// [`Factory`] replaying the journal of an entity before its process is spawned.
+///
+/// `init` builds the empty entity the events are applied to. The commands recorded with the events
+/// are remembered by the process, so that redelivering them after a restart does not apply them twice.
+pub struct Rehydrate<T, F> {
+    projector: EventProjector,
+    init: F,
+    _entity: PhantomData<fn() -> T>,
+}
+
+impl<T, F> Rehydrate<T, F>
+where
+    F: Fn(&EntityId) -> T,
+{
+    pub fn new(projector: EventProjector, init: F) -> Self {
+        Self { projector, init, _entity: PhantomData }
+    }
+}
+
+#[async_trait]
+impl<T, F> Factory<T> for Rehydrate<T, F>
+where
+    T: Process + ResolveMapping,
+    F: Fn(&EntityId) -> T + 'static + Sync + Send,
+{
+    async fn create(&self, id: &EntityId) -> Result<Replayed<T>, Box<dyn Error + Sync + Send>> {
+        let entity = (self.init)(id);
+        Ok(self.projector.replay(id.clone(), entity, 0).await?)
+    }
+}
@@ -4,6 +4,9 @@ mod fixtures;
 pub mod projector;
 pub mod resolver;
 
+#[cfg(feature = "process")]
+pub mod factory;
+
 mod global;
 
 pub use self::global::set_global_projector;
@@ -1,6 +1,10 @@
 use std::collections::BTreeSet;
 
 use nitinol_core::identifier::ToEntityId;
+#[cfg(feature = "process")]
+use nitinol_core::command::CommandId;
+#[cfg(feature = "process")]
+use nitinol_process::factory::{HandledCommand, Replayed};
 use nitinol_protocol::io::{ReadProtocol, Reader};
 use nitinol_protocol::Payload;
 use nitinol_resolver::mapping::{Mapper, ResolveMapping};
@@ -20,6 +24,11 @@ impl EventProjector {
             reader: ReadProtocol::new(reader),
         }
     }
+    
+    /// Read through `protocol`, to keep its configuration such as compression codecs.
+    pub fn from_protocol(protocol: ReadProtocol) -> Self {
+        Self { reader: protocol }
+    }
 }
 
 impl EventProjector {
@@ -62,6 +71,38 @@ impl EventProjector {
         tracing::info!("Replay Successful reading events: {}", replay.1);
         Ok(replay)
     }
+
+    /// Same as [`EventProjector::projection_to_latest`] from `entity` at sequence `from`,
+    /// also collecting the commands recorded with the events to seed the deduplication window of the process.
+    #[cfg(feature = "process")]
+    #[tracing::instrument(skip_all, name = "EventProjector")]
+    pub async fn replay<T: ResolveMapping + nitinol_process::Process>(
+        &self,
+        id: impl ToEntityId,
+        entity: T,
+        from: i64,
+    ) -> Result<Replayed<T>, ProjectionError> {
+        let id = id.to_entity_id();
+        let mut mapping = Mapper::default();
+        T::mapping(&mut mapping);
+
+        let journal = self.reader.read_to_latest(id.clone(), from).await?;
+        let handled = journal
+            .iter()
+            .filter_map(|payload| Some(HandledCommand {
+                id: CommandId::new(payload.command_id.clone()?),
+                sequence: payload.sequence_id,
+                bytes: payload.bytes.clone(),
+            }))
+            .collect();
+        let parts = patch_load(&mapping, journal).await?;
+        let Some((entity, sequence)) = patch(Some(entity), from, parts).await? else {
+            unreachable!("Failed to replay entity: {:?}", id);
+        };
+
+        tracing::info!("Replay Successful reading events: {}", sequence);
+        Ok(Replayed::new(entity, sequence).with_handled(handled))
+    }
 }
 
 async fn patch_load<T: ResolveMapping>(
@@ -0,0 +1,11 @@
+-- The primary key enforces one event per (id, sequence_id) and is the only index needed:
+-- every read filters on `id` and scans a range of `sequence_id`.
+CREATE TABLE IF NOT EXISTS journal (
+    id           TEXT        NOT NULL,
+    sequence_id  BIGINT      NOT NULL,
+    registry_key TEXT        NOT NULL,
+    bytes        BYTEA       NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL,
+    command_id   TEXT,
+    PRIMARY KEY (id, sequence_id)
+);
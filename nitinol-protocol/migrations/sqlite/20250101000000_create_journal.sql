@@ -0,0 +1,11 @@
+-- The primary key enforces one event per (id, sequence_id) and is the only index needed:
+-- every read filters on `id` and scans a range of `sequence_id`.
+CREATE TABLE IF NOT EXISTS journal (
+    id           TEXT    NOT NULL,
+    sequence_id  INTEGER NOT NULL,
+    registry_key TEXT    NOT NULL,
+    bytes        BLOB    NOT NULL,
+    created_at   TEXT    NOT NULL,
+    command_id   TEXT,
+    PRIMARY KEY (id, sequence_id)
+) WITHOUT ROWID;
@@ -0,0 +1 @@
+ALTER TABLE journal ADD COLUMN codec TEXT;
@@ -0,0 +1,143 @@
+//! Compression of [`Payload::bytes`](crate::Payload::bytes).
+//!
+//! A [`WriteProtocol`](crate::io::WriteProtocol) compresses the payloads of the event types
+//! it is configured for, recording the [`Codec::name`] in [`Payload::codec`](crate::Payload::codec).
+//! A [`ReadProtocol`](crate::io::ReadProtocol) decompresses them with the codec of that name,
+//! while payloads without a codec, such as those written before compression was enabled,
+//! are read as they are.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::errors::{ProtocolError, UnknownCodec};
+use crate::Payload;
+
+pub trait Codec: 'static + Sync + Send {
+    /// Marker recorded in the payloads compressed with this codec.
+    ///
+    /// Must never change once payloads were written with it.
+    fn name(&self) -> &'static str;
+    fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>>;
+    fn decompress(&self, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>>;
+}
+
+#[cfg(feature = "zstd")]
+pub use self::zstd::Zstd;
+
+#[cfg(feature = "zstd")]
+mod zstd {
+    use std::error::Error;
+
+    use super::Codec;
+
+    /// Zstandard, favoring the compression ratio.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Zstd {
+        level: i32,
+    }
+
+    impl Zstd {
+        pub fn new(level: i32) -> Zstd {
+            Self { level }
+        }
+    }
+
+    impl Default for Zstd {
+        fn default() -> Self {
+            Self::new(::zstd::DEFAULT_COMPRESSION_LEVEL)
+        }
+    }
+
+    impl Codec for Zstd {
+        fn name(&self) -> &'static str {
+            "zstd"
+        }
+
+        fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+            Ok(::zstd::bulk::compress(bytes, self.level)?)
+        }
+
+        fn decompress(&self, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+            Ok(::zstd::stream::decode_all(bytes)?)
+        }
+    }
+}
+
+#[cfg(feature = "lz4")]
+pub use self::lz4::Lz4;
+
+#[cfg(feature = "lz4")]
+mod lz4 {
+    use std::error::Error;
+
+    use super::Codec;
+
+    /// LZ4, favoring speed.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Lz4;
+
+    impl Codec for Lz4 {
+        fn name(&self) -> &'static str {
+            "lz4"
+        }
+
+        fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+            Ok(lz4_flex::compress_prepend_size(bytes))
+        }
+
+        fn decompress(&self, bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+            Ok(lz4_flex::decompress_size_prepended(bytes)?)
+        }
+    }
+}
+
+/// Codecs used to compress each event type, and to decompress by name.
+#[derive(Clone, Default)]
+pub(crate) struct Codecs {
+    default: Option<Arc<dyn Codec>>,
+    types: HashMap<&'static str, Option<Arc<dyn Codec>>>,
+    names: HashMap<&'static str, Arc<dyn Codec>>,
+}
+
+impl Codecs {
+    pub(crate) fn set_default(&mut self, codec: impl Codec) {
+        self.default = Some(self.register(codec));
+    }
+
+    pub(crate) fn set_for(&mut self, registry_key: &'static str, codec: Option<Arc<dyn Codec>>) {
+        self.types.insert(registry_key, codec);
+    }
+
+    pub(crate) fn register(&mut self, codec: impl Codec) -> Arc<dyn Codec> {
+        let codec: Arc<dyn Codec> = Arc::new(codec);
+        self.names.insert(codec.name(), Arc::clone(&codec));
+        codec
+    }
+
+    pub(crate) fn compress(&self, mut payload: Payload) -> Result<Payload, ProtocolError> {
+        if payload.codec.is_some() {
+            return Ok(payload);
+        }
+        let codec = match self.types.get(payload.registry_key.as_str()) {
+            Some(codec) => codec.as_ref(),
+            None => self.default.as_ref(),
+        };
+        if let Some(codec) = codec {
+            payload.bytes = codec.compress(&payload.bytes).map_err(ProtocolError::Write)?;
+            payload.codec = Some(codec.name().to_string());
+        }
+        Ok(payload)
+    }
+
+    pub(crate) fn decompress(&self, mut payload: Payload) -> Result<Payload, ProtocolError> {
+        let Some(name) = payload.codec.take() else {
+            return Ok(payload);
+        };
+        let codec = self.names
+            .get(name.as_str())
+            .ok_or_else(|| ProtocolError::Read(Box::new(UnknownCodec { name })))?;
+        payload.bytes = codec.decompress(&payload.bytes).map_err(ProtocolError::Read)?;
+        Ok(payload)
+    }
+}
@@ -0,0 +1,187 @@
+//! Encryption of [`Payload::bytes`], for crypto-shredding.
+//!
+//! Every payload is encrypted with the data key of its subject, held in a [`KeyStore`].
+//! The subject is the aggregate id by default, see [`Encryption::with_subject`] to share
+//! a key between aggregates, such as every aggregate holding the data of one user.
+//! Deleting that key with [`Encryption::shred`] makes their payloads unreadable for good:
+//! reading them fails with [`ProtocolError::Shredded`].
+//!
+//! Payloads are encrypted after being compressed, and the marker `aes-256-gcm` is appended
+//! to [`Payload::codec`], as in `zstd+aes-256-gcm`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload as Aad};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+
+use crate::errors::ProtocolError;
+use crate::Payload;
+
+/// Marker of the payloads encrypted by [`Encryption`].
+pub const CIPHER: &str = "aes-256-gcm";
+
+const NONCE: usize = 12;
+
+/// AES-256 key encrypting the payloads of one subject.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DataKey([u8; 32]);
+
+impl DataKey {
+    pub fn generate() -> DataKey {
+        Self(Aes256Gcm::generate_key(OsRng).into())
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> DataKey {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl Debug for DataKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DataKey(<redacted>)")
+    }
+}
+
+/// Storage of the data keys, one per subject.
+#[async_trait]
+pub trait KeyStore: 'static + Sync + Send {
+    /// Key of `subject`, `None` if it was never created or was shredded.
+    async fn get(&self, subject: &str) -> Result<Option<DataKey>, Box<dyn Error + Sync + Send>>;
+
+    /// Key of `subject`, created and stored if missing.
+    async fn get_or_create(&self, subject: &str) -> Result<DataKey, Box<dyn Error + Sync + Send>>;
+
+    /// Delete the key of `subject`. It must never be created again,
+    /// or the payloads written after it would be mistaken for readable ones.
+    async fn shred(&self, subject: &str) -> Result<(), Box<dyn Error + Sync + Send>>;
+}
+
+/// [`KeyStore`] kept in memory, for tests.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryKeyStore {
+    keys: Arc<Mutex<HashMap<String, Option<DataKey>>>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("The key of `{0}` was shredded")]
+struct AlreadyShredded(String);
+
+#[async_trait]
+impl KeyStore for InMemoryKeyStore {
+    async fn get(&self, subject: &str) -> Result<Option<DataKey>, Box<dyn Error + Sync + Send>> {
+        let keys = self.keys.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Ok(keys.get(subject).cloned().flatten())
+    }
+
+    async fn get_or_create(&self, subject: &str) -> Result<DataKey, Box<dyn Error + Sync + Send>> {
+        let mut keys = self.keys.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match keys.entry(subject.to_string()).or_insert_with(|| Some(DataKey::generate())) {
+            Some(key) => Ok(key.clone()),
+            None => Err(Box::new(AlreadyShredded(subject.to_string()))),
+        }
+    }
+
+    async fn shred(&self, subject: &str) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let mut keys = self.keys.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        keys.insert(subject.to_string(), None);
+        Ok(())
+    }
+}
+
+/// Encrypts and decrypts payloads with the keys of a [`KeyStore`].
+///
+/// Given to [`WriteProtocol::with_encryption`](crate::io::WriteProtocol::with_encryption) and
+/// [`ReadProtocol::with_encryption`](crate::io::ReadProtocol::with_encryption).
+#[derive(Clone)]
+pub struct Encryption {
+    keys: Arc<dyn KeyStore>,
+    subject: Arc<dyn Fn(&EntityId) -> String + Sync + Send>,
+}
+
+impl Encryption {
+    pub fn new(keys: impl KeyStore) -> Encryption {
+        Self {
+            keys: Arc::new(keys),
+            subject: Arc::new(ToString::to_string),
+        }
+    }
+
+    /// Subject whose key encrypts the payloads of an aggregate. Defaults to the aggregate id.
+    ///
+    /// Must only depend on the id, as payloads are decrypted before anything else is known of them.
+    pub fn with_subject(mut self, subject: impl Fn(&EntityId) -> String + 'static + Sync + Send) -> Self {
+        self.subject = Arc::new(subject);
+        self
+    }
+
+    /// Delete the key of the subject of `id`, making its payloads unreadable.
+    pub async fn shred(&self, id: &EntityId) -> Result<(), ProtocolError> {
+        self.keys.shred(&(self.subject)(id)).await.map_err(ProtocolError::Write)
+    }
+
+    pub(crate) async fn encrypt(&self, mut payload: Payload) -> Result<Payload, ProtocolError> {
+        if is_encrypted(&payload) {
+            return Ok(payload);
+        }
+        let subject = (self.subject)(&EntityId::new(payload.id.clone()));
+        let key = self.keys.get_or_create(&subject).await.map_err(ProtocolError::Write)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = associated(&payload);
+        let ciphertext = cipher(&key)
+            .encrypt(&nonce, Aad { msg: &payload.bytes, aad: &aad })
+            .map_err(|e| ProtocolError::Write(e.to_string().into()))?;
+        payload.bytes = [nonce.as_slice(), &ciphertext].concat();
+        payload.codec = Some(match payload.codec.take() {
+            Some(codec) => format!("{codec}+{CIPHER}"),
+            None => CIPHER.to_string(),
+        });
+        Ok(payload)
+    }
+
+    pub(crate) async fn decrypt(&self, mut payload: Payload) -> Result<Payload, ProtocolError> {
+        if !is_encrypted(&payload) {
+            return Ok(payload);
+        }
+        let subject = (self.subject)(&EntityId::new(payload.id.clone()));
+        let key = self.keys.get(&subject).await
+            .map_err(ProtocolError::Read)?
+            .ok_or_else(|| ProtocolError::Shredded { id: payload.id.clone(), seq: payload.sequence_id })?;
+        if payload.bytes.len() < NONCE {
+            return Err(ProtocolError::Read("encrypted payload is truncated".into()));
+        }
+        let (nonce, ciphertext) = payload.bytes.split_at(NONCE);
+        let aad = associated(&payload);
+        let plaintext = cipher(&key)
+            .decrypt(Nonce::from_slice(nonce), Aad { msg: ciphertext, aad: &aad })
+            .map_err(|e| ProtocolError::Read(e.to_string().into()))?;
+        payload.bytes = plaintext;
+        payload.codec = payload.codec
+            .as_deref()
+            .and_then(|codec| codec.strip_suffix(CIPHER))
+            .and_then(|codec| codec.strip_suffix('+'))
+            .map(ToString::to_string);
+        Ok(payload)
+    }
+}
+
+fn is_encrypted(payload: &Payload) -> bool {
+    payload.codec.as_deref().is_some_and(|codec| codec.rsplit('+').next() == Some(CIPHER))
+}
+
+fn cipher(key: &DataKey) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_bytes()))
+}
+
+/// Binds the ciphertext to its place in the journal, so it cannot be moved to another one.
+fn associated(payload: &Payload) -> Vec<u8> {
+    format!("{}:{}:{}", payload.id, payload.sequence_id, payload.registry_key).into_bytes()
+}
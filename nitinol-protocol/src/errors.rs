@@ -8,4 +8,35 @@ pub enum ProtocolError {
     Write(#[source] Box<dyn Error + Sync + Send>),
     #[error("Failed to read data: {0}")]
     Read(#[source] Box<dyn Error + Sync + Send>),
+    #[error("Sequence {seq} of {id} conflicts with an event already written")]
+    Conflict { id: String, seq: i64 },
+    #[error("Event {seq} of {id} is unreadable, its key was shredded")]
+    Shredded { id: String, seq: i64 },
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("No event {seq} for {id}")]
+pub struct NotFound {
+    pub id: String,
+    pub seq: i64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Segment {segment} is corrupted at offset {offset}")]
+pub struct Corrupted {
+    pub segment: u64,
+    pub offset: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Stored payload {seq} of {id} is corrupted")]
+pub struct CorruptedPayload {
+    pub id: String,
+    pub seq: i64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Payload is compressed with `{name}`, which is not registered")]
+pub struct UnknownCodec {
+    pub name: String,
 }
@@ -0,0 +1,412 @@
+//! Journal stored in append-only log files, for deployments with only a local disk.
+//!
+//! Payloads are appended to segment files of a directory, a new segment being started
+//! once the current one exceeds [`FileOptions::with_segment_size`]. Only the last segment
+//! is ever written to. The offset of every payload, per aggregate and sequence,
+//! is kept in memory and rebuilt by scanning the segments when the journal is opened.
+//!
+//! A batch is written as one frame covered by a single checksum. A crash can leave the last
+//! record or batch of the last segment partially written. Opening the journal truncates such
+//! a torn tail, dropping a torn batch as a whole, while corruption anywhere else is an error.
+//!
+//! Disk access runs on the blocking threads of the tokio runtime.
+
+mod segment;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+
+use crate::errors::{Corrupted, NotFound, ProtocolError};
+use crate::io::{Reader, Writer};
+use crate::record;
+use crate::Payload;
+
+use self::segment::{sync_dir, Segment};
+
+/// When written payloads are flushed to the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// After every write, before it returns.
+    Always,
+    /// At most once per interval: with a write once the interval elapsed since the last flush,
+    /// otherwise from a timer the interval after it, and when the journal is dropped.
+    Interval(Duration),
+    /// Left to the operating system.
+    Never,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileOptions {
+    segment_size: u64,
+    fsync: FsyncPolicy,
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        Self {
+            segment_size: 64 * 1024 * 1024,
+            fsync: FsyncPolicy::Always,
+        }
+    }
+}
+
+impl FileOptions {
+    /// Size in bytes after which a new segment is started. Defaults to 64 MiB.
+    ///
+    /// A batch is never split, so a segment can exceed it by the size of one batch.
+    pub fn with_segment_size(mut self, size: u64) -> Self {
+        self.segment_size = size;
+        self
+    }
+
+    /// Defaults to [`FsyncPolicy::Always`].
+    pub fn with_fsync(mut self, policy: FsyncPolicy) -> Self {
+        self.fsync = policy;
+        self
+    }
+}
+
+/// Journal stored in segmented, append-only files. See the [module documentation](self).
+///
+/// Follows the semantics of [`InMemoryEventStore`](crate::inmemory::InMemoryEventStore)
+/// when it comes to sequences and batches. Clones share the same files.
+#[derive(Clone)]
+pub struct FileJournal {
+    inner: Arc<Mutex<State>>,
+}
+
+struct State {
+    dir: PathBuf,
+    options: FileOptions,
+    segments: BTreeMap<u64, Segment>,
+    index: HashMap<String, BTreeMap<i64, Location>>,
+    synced: Instant,
+    /// Written payloads are not flushed yet.
+    dirty: bool,
+    /// A timer is pending to flush them.
+    scheduled: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    segment: u64,
+    offset: u64,
+}
+
+impl FileJournal {
+    pub fn open(dir: impl AsRef<Path>) -> Result<FileJournal, ProtocolError> {
+        Self::open_with(dir, FileOptions::default())
+    }
+
+    /// Open the journal stored in `dir`, creating it if missing.
+    pub fn open_with(dir: impl AsRef<Path>, options: FileOptions) -> Result<FileJournal, ProtocolError> {
+        let setup = |e: io::Error| ProtocolError::Setup(Box::new(e));
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(setup)?;
+
+        let mut indices = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(setup)? {
+            let path = entry.map_err(setup)?.path();
+            match Segment::parse(&path) {
+                Some(index) => indices.push(index),
+                // Left over by a compaction interrupted before its rename.
+                None if path.extension().is_some_and(|ext| ext == "tmp") => fs::remove_file(&path).map_err(setup)?,
+                None => {}
+            }
+        }
+        indices.sort_unstable();
+
+        let mut state = State {
+            dir,
+            options,
+            segments: BTreeMap::new(),
+            index: HashMap::new(),
+            synced: Instant::now(),
+            dirty: false,
+            scheduled: false,
+        };
+        let last = indices.last().copied();
+        for index in indices {
+            let mut segment = Segment::open(&state.dir, index).map_err(setup)?;
+            let scanned = segment.scan().map_err(setup)?;
+            if scanned.valid < segment.len {
+                if Some(index) != last {
+                    return Err(ProtocolError::Setup(Box::new(Corrupted { segment: index, offset: scanned.valid })));
+                }
+                segment.truncate(scanned.valid).map_err(setup)?;
+            }
+            for (payload, offset) in scanned.records {
+                state.locate(&payload, Location { segment: index, offset });
+            }
+            state.segments.insert(index, segment);
+        }
+        if state.segments.is_empty() {
+            state.segments.insert(0, Segment::open(&state.dir, 0).map_err(setup)?);
+        }
+
+        Ok(Self { inner: Arc::new(Mutex::new(state)) })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.inner)
+    }
+
+    /// Run `f` on the state from a blocking thread, reporting a failure to run it with `fail`.
+    async fn blocking<R: Send + 'static>(
+        &self,
+        fail: fn(Box<dyn std::error::Error + Sync + Send>) -> ProtocolError,
+        f: impl FnOnce(&mut State) -> Result<R, ProtocolError> + Send + 'static,
+    ) -> Result<R, ProtocolError> {
+        let inner = Arc::clone(&self.inner);
+        match tokio::task::spawn_blocking(move || f(&mut lock(&inner))).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(fail(Box::new(e))),
+        }
+    }
+
+    async fn append(&self, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        let delay = self.blocking(ProtocolError::Write, move |state| {
+            state.append(&payloads)?;
+            Ok(state.schedule())
+        }).await?;
+        if let Some(delay) = delay {
+            tokio::spawn(flush_after(Arc::downgrade(&self.inner), delay));
+        }
+        Ok(())
+    }
+
+    /// Flush written payloads to the disk, whatever the [`FsyncPolicy`].
+    pub async fn sync(&self) -> Result<(), ProtocolError> {
+        self.blocking(ProtocolError::Write, |state| {
+            state.sync().map_err(|e| ProtocolError::Write(Box::new(e)))
+        }).await
+    }
+
+    pub fn latest_sequence(&self, id: &EntityId) -> Option<i64> {
+        self.lock().latest(id.as_ref())
+    }
+
+    /// Number of segment files.
+    pub fn segments(&self) -> usize {
+        self.lock().segments.len()
+    }
+
+    /// Rewrite every segment but the last one, keeping only the payloads `retain` returns `true` for.
+    ///
+    /// Segments left empty are removed. Returns the number of payloads dropped.
+    /// The latest payload of an aggregate cannot be dropped, as its sequence could then be written again:
+    /// the compaction fails with [`ProtocolError::Conflict`] without changing anything.
+    ///
+    /// Segments are rewritten one after the other. If one fails, those already rewritten stay compacted
+    /// and the others are left as they were.
+    pub async fn compact(&self, retain: impl FnMut(&Payload) -> bool + Send + 'static) -> Result<usize, ProtocolError> {
+        self.blocking(ProtocolError::Write, |state| state.compact(retain)).await
+    }
+}
+
+fn lock(inner: &Mutex<State>) -> MutexGuard<'_, State> {
+    inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Flush the payloads written since the last flush once `delay` elapsed, unless the journal was dropped.
+async fn flush_after(inner: Weak<Mutex<State>>, delay: Duration) {
+    tokio::time::sleep(delay).await;
+    let Some(inner) = inner.upgrade() else {
+        return;
+    };
+    let _ = tokio::task::spawn_blocking(move || {
+        let mut state = lock(&inner);
+        state.scheduled = false;
+        if state.dirty {
+            // A failure is reported by the next write, which flushes as the interval elapsed.
+            let _ = state.sync();
+        }
+    }).await;
+}
+
+impl State {
+    fn locate(&mut self, payload: &Payload, location: Location) {
+        self.index
+            .entry(payload.id.clone())
+            .or_default()
+            .insert(payload.sequence_id, location);
+    }
+
+    fn latest(&self, id: &str) -> Option<i64> {
+        self.index.get(id).and_then(|sequences| sequences.keys().next_back().copied())
+    }
+
+    fn active(&mut self) -> &mut Segment {
+        self.segments.values_mut().next_back().expect("a journal always has a segment")
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.active().sync()?;
+        self.synced = Instant::now();
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Delay after which a timer must flush the payloads left unflushed by [`FsyncPolicy::Interval`],
+    /// `None` if there are none or a timer is already pending.
+    fn schedule(&mut self) -> Option<Duration> {
+        let FsyncPolicy::Interval(interval) = self.options.fsync else {
+            return None;
+        };
+        if !self.dirty || self.scheduled {
+            return None;
+        }
+        self.scheduled = true;
+        Some(interval.saturating_sub(self.synced.elapsed()))
+    }
+
+    fn append(&mut self, payloads: &[Payload]) -> Result<(), ProtocolError> {
+        let mut latest = HashMap::new();
+        for payload in payloads {
+            let current = latest.get(&payload.id).copied().or_else(|| self.latest(&payload.id));
+            if current.is_some_and(|current| payload.sequence_id <= current) {
+                return Err(ProtocolError::Conflict { id: payload.id.clone(), seq: payload.sequence_id });
+            }
+            latest.insert(payload.id.clone(), payload.sequence_id);
+        }
+
+        let mut bytes = Vec::new();
+        let offsets = record::encode_batch(payloads, &mut bytes);
+
+        let write = |e: io::Error| ProtocolError::Write(Box::new(e));
+        let active = self.active();
+        if active.len > 0 && active.len + bytes.len() as u64 > self.options.segment_size {
+            self.roll().map_err(write)?;
+        }
+        let active = self.active();
+        let (segment, start) = (active.index, active.len);
+        active.append(&bytes).map_err(write)?;
+
+        match self.options.fsync {
+            FsyncPolicy::Always => self.sync().map_err(write)?,
+            FsyncPolicy::Interval(interval) if self.synced.elapsed() >= interval => self.sync().map_err(write)?,
+            FsyncPolicy::Interval(_) => self.dirty = true,
+            FsyncPolicy::Never => {}
+        }
+
+        for (payload, offset) in payloads.iter().zip(offsets) {
+            self.locate(payload, Location { segment, offset: start + offset });
+        }
+        Ok(())
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        if self.options.fsync != FsyncPolicy::Never {
+            self.active().sync()?;
+        }
+        let next = self.active().index + 1;
+        self.segments.insert(next, Segment::open(&self.dir, next)?);
+        sync_dir(&self.dir)
+    }
+
+    fn read(&mut self, location: Location) -> Result<Payload, ProtocolError> {
+        self.segments
+            .get_mut(&location.segment)
+            .ok_or_else(|| ProtocolError::Read(Box::new(Corrupted { segment: location.segment, offset: location.offset })))?
+            .read(location.offset)
+            .map_err(|e| ProtocolError::Read(Box::new(e)))
+    }
+
+    /// See [`FileJournal::compact`].
+    fn compact(&mut self, mut retain: impl FnMut(&Payload) -> bool) -> Result<usize, ProtocolError> {
+        let write = |e: io::Error| ProtocolError::Write(Box::new(e));
+        let sealed = self.segments.keys().rev().skip(1).rev().copied().collect::<Vec<_>>();
+
+        let mut plan = Vec::new();
+        for number in sealed {
+            let records = self.segments[&number].scan().map_err(write)?.records;
+            let (kept, removed): (Vec<_>, Vec<_>) = records.into_iter()
+                .map(|(payload, _)| payload)
+                .partition(|payload| retain(payload));
+            if let Some(latest) = removed.iter().find(|payload| self.latest(&payload.id) == Some(payload.sequence_id)) {
+                return Err(ProtocolError::Conflict { id: latest.id.clone(), seq: latest.sequence_id });
+            }
+            if !removed.is_empty() {
+                plan.push((number, kept, removed));
+            }
+        }
+
+        let mut dropped = 0;
+        for (number, kept, removed) in plan {
+            // The index keeps pointing to the segment as it was until its new content is on disk.
+            if kept.is_empty() {
+                self.segments[&number].remove(&self.dir).map_err(write)?;
+                self.segments.remove(&number);
+            } else {
+                let segment = self.segments.get_mut(&number).expect("listed segment");
+                let offsets = segment.rewrite(&self.dir, &kept).map_err(write)?;
+                for (payload, offset) in kept.iter().zip(offsets) {
+                    if let Some(location) = self.index.get_mut(&payload.id).and_then(|sequences| sequences.get_mut(&payload.sequence_id)) {
+                        location.offset = offset;
+                    }
+                }
+            }
+            for payload in &removed {
+                if let Some(sequences) = self.index.get_mut(&payload.id) {
+                    sequences.remove(&payload.sequence_id);
+                }
+            }
+            dropped += removed.len();
+        }
+        Ok(dropped)
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if self.dirty {
+            let _ = self.active().sync();
+        }
+    }
+}
+
+#[async_trait]
+impl Writer for FileJournal {
+    async fn write(&self, _: EntityId, payload: Payload) -> Result<(), ProtocolError> {
+        self.append(vec![payload]).await
+    }
+
+    async fn write_batch(&self, _: EntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        self.append(payloads).await
+    }
+}
+
+#[async_trait]
+impl Reader for FileJournal {
+    async fn read(&self, id: EntityId, seq: i64) -> Result<Payload, ProtocolError> {
+        self.blocking(ProtocolError::Read, move |state| {
+            let location = state.index
+                .get(id.as_ref())
+                .and_then(|sequences| sequences.get(&seq))
+                .copied()
+                .ok_or_else(|| ProtocolError::Read(Box::new(NotFound { id: id.to_string(), seq })))?;
+            state.read(location)
+        }).await
+    }
+
+    async fn read_to(&self, id: EntityId, from: i64, to: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
+        if from > to {
+            return Ok(BTreeSet::new());
+        }
+        self.blocking(ProtocolError::Read, move |state| {
+            let locations = state.index
+                .get(id.as_ref())
+                .map(|sequences| sequences.range(from..=to).map(|(_, location)| *location).collect::<Vec<_>>())
+                .unwrap_or_default();
+            locations.into_iter().map(|location| state.read(location)).collect()
+        }).await
+    }
+}
@@ -0,0 +1,146 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::Payload;
+
+use crate::record::{self, BATCH, HEADER};
+
+/// One log file of a [`FileJournal`](super::FileJournal), named after its index.
+pub(crate) struct Segment {
+    pub(crate) index: u64,
+    pub(crate) len: u64,
+    path: PathBuf,
+    file: File,
+}
+
+/// Records of a segment, up to the first torn or corrupted one.
+pub(crate) struct Scanned {
+    pub(crate) records: Vec<(Payload, u64)>,
+    pub(crate) valid: u64,
+}
+
+impl Segment {
+    pub(crate) fn path(dir: &Path, index: u64) -> PathBuf {
+        dir.join(format!("{index:020}.log"))
+    }
+
+    /// Index of the segment stored at `path`, if it is one.
+    pub(crate) fn parse(path: &Path) -> Option<u64> {
+        if path.extension()? != "log" {
+            return None;
+        }
+        path.file_stem()?.to_str()?.parse().ok()
+    }
+
+    pub(crate) fn open(dir: &Path, index: u64) -> io::Result<Segment> {
+        let path = Self::path(dir, index);
+        let file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { index, len, path, file })
+    }
+
+    pub(crate) fn scan(&self) -> io::Result<Scanned> {
+        let bytes = fs::read(&self.path)?;
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while let Some(head) = bytes.get(offset..offset + HEADER) {
+            let (len, crc) = record::header(head.try_into().unwrap());
+            let Some(body) = bytes.get(offset + HEADER..).and_then(|tail| tail.get(..len & !(BATCH as usize))) else {
+                break;
+            };
+            if len & BATCH as usize == 0 {
+                let Some(payload) = record::decode(body, crc) else {
+                    break;
+                };
+                records.push((payload, offset as u64));
+            } else {
+                // A batch whose frame is torn or corrupted was not committed, none of its records are kept.
+                let Some(batch) = (record::crc32(body) == crc).then(|| Self::batch(body, offset + HEADER)).flatten() else {
+                    break;
+                };
+                records.extend(batch);
+            }
+            offset += HEADER + body.len();
+        }
+        Ok(Scanned { records, valid: offset as u64 })
+    }
+
+    /// Records of a batch frame whose body starts at `start`.
+    fn batch(body: &[u8], start: usize) -> Option<Vec<(Payload, u64)>> {
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while offset < body.len() {
+            let (len, crc) = record::header(body.get(offset..offset + HEADER)?.try_into().unwrap());
+            let payload = record::decode(body.get(offset + HEADER..)?.get(..len)?, crc)?;
+            records.push((payload, (start + offset) as u64));
+            offset += HEADER + len;
+        }
+        Some(records)
+    }
+
+    pub(crate) fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.file.set_len(len)?;
+        self.file.sync_all()?;
+        self.len = len;
+        Ok(())
+    }
+
+    /// Append encoded records, rolling back to the previous length if the write fails.
+    pub(crate) fn append(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if let Err(e) = self.file.write_all(bytes) {
+            let _ = self.file.set_len(self.len);
+            return Err(e);
+        }
+        self.len += bytes.len() as u64;
+        Ok(())
+    }
+
+    pub(crate) fn read(&mut self, offset: u64) -> io::Result<Payload> {
+        let mut head = [0; HEADER];
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(&mut head)?;
+        let (len, crc) = record::header(&head);
+        let mut body = vec![0; len];
+        self.file.read_exact(&mut body)?;
+        record::decode(&body, crc).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "corrupted record"))
+    }
+
+    pub(crate) fn sync(&self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    /// Replace the content of this segment with `records`, atomically through a temporary file.
+    ///
+    /// Returns the offset of each record in the new file.
+    pub(crate) fn rewrite(&mut self, dir: &Path, records: &[Payload]) -> io::Result<Vec<u64>> {
+        let mut bytes = Vec::new();
+        let mut offsets = Vec::with_capacity(records.len());
+        for payload in records {
+            offsets.push(bytes.len() as u64);
+            record::encode(payload, &mut bytes);
+        }
+        let tmp = self.path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        sync_dir(dir)?;
+        *self = Self::open(dir, self.index)?;
+        Ok(offsets)
+    }
+
+    pub(crate) fn remove(&self, dir: &Path) -> io::Result<()> {
+        fs::remove_file(&self.path)?;
+        sync_dir(dir)
+    }
+}
+
+/// Make renames and removals in `dir` durable.
+pub(crate) fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
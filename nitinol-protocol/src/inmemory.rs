@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+
+use crate::errors::{NotFound, ProtocolError};
+use crate::io::{Reader, Writer};
+use crate::Payload;
+
+/// Event store kept in memory, implementing both [`Reader`] and [`Writer`].
+///
+/// It doubles as the reference semantics expected from other backends:
+/// - The sequence of an aggregate only grows. Writing at or below the latest sequence
+///   fails with [`ProtocolError::Conflict`], gaps are allowed.
+/// - [`Writer::write_batch`] is atomic, either every payload is written or none.
+/// - Every payload gets a position in a global log, following the order of the writes.
+/// - [`Reader::read_to`] is inclusive on both ends.
+///
+/// Clones share the same storage.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryEventStore {
+    inner: Arc<Mutex<Store>>,
+}
+
+#[derive(Debug, Default)]
+struct Store {
+    log: Vec<Payload>,
+    index: HashMap<String, BTreeMap<i64, usize>>,
+}
+
+impl Store {
+    fn check(&self, payload: &Payload, latest: Option<i64>) -> Result<(), ProtocolError> {
+        let latest = latest.or_else(|| self.latest(&payload.id));
+        match latest {
+            Some(latest) if payload.sequence_id <= latest => Err(ProtocolError::Conflict {
+                id: payload.id.clone(),
+                seq: payload.sequence_id,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn latest(&self, id: &str) -> Option<i64> {
+        self.index.get(id).and_then(|index| index.keys().next_back().copied())
+    }
+
+    fn append(&mut self, payload: Payload) {
+        self.index
+            .entry(payload.id.clone())
+            .or_default()
+            .insert(payload.sequence_id, self.log.len());
+        self.log.push(payload);
+    }
+
+    fn payloads(&self, id: &str, from: i64, to: i64) -> impl Iterator<Item = &Payload> {
+        self.index
+            .get(id)
+            .into_iter()
+            .flat_map(move |index| index.range(from..=to).map(|(_, position)| &self.log[*position]))
+    }
+}
+
+impl InMemoryEventStore {
+    pub fn new() -> InMemoryEventStore {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Store> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Payloads of `id`, in sequence order.
+    pub fn payloads(&self, id: &EntityId) -> Vec<Payload> {
+        self.lock()
+            .payloads(id.as_ref(), i64::MIN, i64::MAX)
+            .cloned()
+            .collect()
+    }
+
+    /// Every payload from global position `from`, in the order they were written.
+    pub fn since(&self, from: usize) -> Vec<Payload> {
+        self.lock().log.iter().skip(from).cloned().collect()
+    }
+
+    pub fn latest_sequence(&self, id: &EntityId) -> Option<i64> {
+        self.lock().latest(id.as_ref())
+    }
+
+    /// Aggregates that have at least one payload.
+    pub fn ids(&self) -> Vec<EntityId> {
+        self.lock().index.keys().cloned().map(EntityId::new).collect()
+    }
+
+    /// Number of payloads of every aggregate.
+    pub fn len(&self) -> usize {
+        self.lock().log.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut store = self.lock();
+        store.log.clear();
+        store.index.clear();
+    }
+}
+
+#[async_trait]
+impl Writer for InMemoryEventStore {
+    async fn write(&self, _: EntityId, payload: Payload) -> Result<(), ProtocolError> {
+        let mut store = self.lock();
+        store.check(&payload, None)?;
+        store.append(payload);
+        Ok(())
+    }
+
+    async fn write_batch(&self, _: EntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        let mut store = self.lock();
+        let mut latest = HashMap::new();
+        for payload in &payloads {
+            store.check(payload, latest.get(&payload.id).copied())?;
+            latest.insert(payload.id.clone(), payload.sequence_id);
+        }
+        for payload in payloads {
+            store.append(payload);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Reader for InMemoryEventStore {
+    async fn read(&self, id: EntityId, seq: i64) -> Result<Payload, ProtocolError> {
+        self.lock()
+            .payloads(id.as_ref(), seq, seq)
+            .next()
+            .cloned()
+            .ok_or_else(|| ProtocolError::Read(Box::new(NotFound { id: id.to_string(), seq })))
+    }
+
+    async fn read_to(&self, id: EntityId, from: i64, to: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
+        if from > to {
+            return Ok(BTreeSet::new());
+        }
+        Ok(self.lock().payloads(id.as_ref(), from, to).cloned().collect())
+    }
+}
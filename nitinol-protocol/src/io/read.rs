@@ -4,6 +4,9 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use nitinol_core::event::Event;
 use nitinol_core::identifier::{EntityId, ToEntityId};
+use crate::codec::{Codec, Codecs};
+#[cfg(feature = "encryption")]
+use crate::crypto::Encryption;
 use crate::errors::ProtocolError;
 use crate::Payload;
 
@@ -19,6 +22,9 @@ pub trait Reader: 'static + Sync + Send {
 
 pub struct ReadProtocol {
     reader: Arc<dyn Reader>,
+    codecs: Codecs,
+    #[cfg(feature = "encryption")]
+    encryption: Option<Encryption>,
 }
 
 impl Debug for ReadProtocol {
@@ -31,6 +37,9 @@ impl Clone for ReadProtocol {
     fn clone(&self) -> Self {
         Self {
             reader: Arc::clone(&self.reader),
+            codecs: self.codecs.clone(),
+            #[cfg(feature = "encryption")]
+            encryption: self.encryption.clone(),
         }
     }
 }
@@ -39,20 +48,57 @@ impl ReadProtocol {
     pub fn new(provider: impl Reader) -> Self {
         Self {
             reader: Arc::new(provider),
+            codecs: Codecs::default(),
+            #[cfg(feature = "encryption")]
+            encryption: None,
         }
     }
     
+    /// Decrypt the payloads encrypted by [`WriteProtocol::with_encryption`](crate::io::WriteProtocol::with_encryption).
+    ///
+    /// Payloads whose key was shredded fail with [`ProtocolError::Shredded`].
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+    
+    /// Decompress the payloads marked with the name of `codec`.
+    pub fn with_codec(mut self, codec: impl Codec) -> Self {
+        self.codecs.register(codec);
+        self
+    }
+    
     pub async fn read<E: Event>(&self, id: impl ToEntityId, seq: i64) -> Result<E, ProtocolError> {
-        let payload = self.reader.read(id.to_entity_id(), seq).await?;
+        let payload = self.decode(self.reader.read(id.to_entity_id(), seq).await?).await?;
         E::from_bytes(&payload.bytes)
             .map_err(|e| ProtocolError::Read(Box::new(e)))
     }
     
     pub async fn read_to(&self, id: impl ToEntityId, from: i64, to: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
-        self.reader.read_to(id.to_entity_id(), from, to).await
+        let payloads = self.reader.read_to(id.to_entity_id(), from, to).await?;
+        self.decode_all(payloads).await
     }
     
     pub async fn read_to_latest(&self, id: impl ToEntityId, from: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
-        self.reader.read_to_latest(id.to_entity_id(), from).await
+        let payloads = self.reader.read_to_latest(id.to_entity_id(), from).await?;
+        self.decode_all(payloads).await
+    }
+    
+    async fn decode(&self, payload: Payload) -> Result<Payload, ProtocolError> {
+        #[cfg(feature = "encryption")]
+        let payload = match &self.encryption {
+            Some(encryption) => encryption.decrypt(payload).await?,
+            None => payload,
+        };
+        self.codecs.decompress(payload)
+    }
+    
+    async fn decode_all(&self, payloads: BTreeSet<Payload>) -> Result<BTreeSet<Payload>, ProtocolError> {
+        let mut decoded = BTreeSet::new();
+        for payload in payloads {
+            decoded.insert(self.decode(payload).await?);
+        }
+        Ok(decoded)
     }
 }
\ No newline at end of file
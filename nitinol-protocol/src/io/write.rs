@@ -2,18 +2,35 @@ use std::fmt::Debug;
 use std::sync::Arc;
 use async_trait::async_trait;
 use time::OffsetDateTime;
+use nitinol_core::command::CommandId;
 use nitinol_core::event::Event;
 use nitinol_core::identifier::{EntityId, ToEntityId};
+use crate::codec::{Codec, Codecs};
+#[cfg(feature = "encryption")]
+use crate::crypto::Encryption;
 use crate::errors::ProtocolError;
 use crate::Payload;
 
 #[async_trait]
 pub trait Writer: 'static + Sync + Send {
     async fn write(&self, aggregate_id: EntityId, payload: Payload) -> Result<(), ProtocolError>;
+    
+    /// Write several payloads of one aggregate.
+    /// 
+    /// Writes them one by one by default, override it to use a single round trip.
+    async fn write_batch(&self, aggregate_id: EntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        for payload in payloads {
+            self.write(aggregate_id.clone(), payload).await?;
+        }
+        Ok(())
+    }
 }
 
 pub struct WriteProtocol {
-    writer: Arc<dyn Writer>
+    writer: Arc<dyn Writer>,
+    codecs: Codecs,
+    #[cfg(feature = "encryption")]
+    encryption: Option<Encryption>,
 }
 
 impl Debug for WriteProtocol {
@@ -24,26 +41,94 @@ impl Debug for WriteProtocol {
 
 impl Clone for WriteProtocol {
     fn clone(&self) -> Self {
-        Self { writer: Arc::clone(&self.writer) }
+        Self {
+            writer: Arc::clone(&self.writer),
+            codecs: self.codecs.clone(),
+            #[cfg(feature = "encryption")]
+            encryption: self.encryption.clone(),
+        }
     }
 }
 
 impl WriteProtocol {
     pub fn new(provider: impl Writer) -> Self {
-        Self { writer: Arc::new(provider) }
+        Self {
+            writer: Arc::new(provider),
+            codecs: Codecs::default(),
+            #[cfg(feature = "encryption")]
+            encryption: None,
+        }
+    }
+    
+    /// Encrypt every payload, after compressing it. See [`crate::crypto`].
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+    
+    /// Compress the payloads of every event type with `codec`.
+    pub fn with_compression(mut self, codec: impl Codec) -> Self {
+        self.codecs.set_default(codec);
+        self
+    }
+    
+    /// Compress the payloads of `E` with `codec`, instead of the codec set by [`WriteProtocol::with_compression`].
+    pub fn with_compression_for<E: Event>(mut self, codec: impl Codec) -> Self {
+        let codec = self.codecs.register(codec);
+        for &key in E::EVENT_TYPES {
+            self.codecs.set_for(key, Some(codec.clone()));
+        }
+        self
+    }
+    
+    /// Write the payloads of `E` uncompressed, whatever the codec set by [`WriteProtocol::with_compression`].
+    pub fn without_compression_for<E: Event>(mut self) -> Self {
+        for &key in E::EVENT_TYPES {
+            self.codecs.set_for(key, None);
+        }
+        self
     }
     
     pub async fn write<E: Event>(&self, aggregate_id: impl ToEntityId, event: &E, seq: i64) -> Result<(), ProtocolError> {
+        self.write_with(aggregate_id, event, seq, None).await
+    }
+    
+    /// Same as [`WriteProtocol::write`], recording the id of the command that produced `event`.
+    pub async fn write_with<E: Event>(&self, aggregate_id: impl ToEntityId, event: &E, seq: i64, command_id: Option<&CommandId>) -> Result<(), ProtocolError> {
+        let registry_key = event.event_type().to_string();
         let event = event.as_bytes().map_err(|e| ProtocolError::Write(Box::new(e)))?;
         let aggregate_id = aggregate_id.to_entity_id();
+        let payload = self.encode(Payload {
+            id: aggregate_id.to_string(),
+            sequence_id: seq,
+            registry_key,
+            bytes: event,
+            created_at: OffsetDateTime::now_utc(),
+            command_id: command_id.map(ToString::to_string),
+            codec: None
+        }).await?;
         self.writer
-            .write(aggregate_id.clone(), Payload {
-                id: aggregate_id.to_string(),
-                sequence_id: seq,
-                registry_key: E::EVENT_TYPE.to_string(),
-                bytes: event,
-                created_at: OffsetDateTime::now_utc()
-            })
+            .write(aggregate_id, payload)
             .await
     }
+    
+    pub async fn write_batch(&self, aggregate_id: impl ToEntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        let mut encoded = Vec::with_capacity(payloads.len());
+        for payload in payloads {
+            encoded.push(self.encode(payload).await?);
+        }
+        self.writer
+            .write_batch(aggregate_id.to_entity_id(), encoded)
+            .await
+    }
+    
+    async fn encode(&self, payload: Payload) -> Result<Payload, ProtocolError> {
+        let payload = self.codecs.compress(payload)?;
+        #[cfg(feature = "encryption")]
+        if let Some(encryption) = &self.encryption {
+            return encryption.encrypt(payload).await;
+        }
+        Ok(payload)
+    }
 }
@@ -0,0 +1,171 @@
+//! Journal stored in an embedded [redb](https://docs.rs/redb) database, running in-process.
+//!
+//! Payloads are keyed by `(id, sequence_id)`, so that the payloads of one aggregate are contiguous
+//! and [`Reader::read_to`] is a single range scan. A second table maps a global position,
+//! assigned in write order, to that key, for scans across aggregates with [`RedbJournal::since`].
+//!
+//! Every transaction runs on the blocking threads of the tokio runtime.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+use redb::{Database, ReadableTable, TableDefinition};
+
+use crate::errors::{CorruptedPayload, NotFound, ProtocolError};
+use crate::io::{Reader, Writer};
+use crate::record;
+use crate::Payload;
+
+const EVENTS: TableDefinition<(&str, i64), &[u8]> = TableDefinition::new("events");
+const LOG: TableDefinition<u64, (&str, i64)> = TableDefinition::new("log");
+
+/// Journal stored in a redb database. See the [module documentation](self).
+///
+/// Follows the semantics of [`InMemoryEventStore`](crate::inmemory::InMemoryEventStore)
+/// when it comes to sequences and batches. Clones share the same database.
+#[derive(Clone)]
+pub struct RedbJournal {
+    db: Arc<Database>,
+}
+
+fn setup(e: impl Into<redb::Error>) -> ProtocolError {
+    ProtocolError::Setup(Box::new(e.into()))
+}
+
+fn write(e: impl Into<redb::Error>) -> ProtocolError {
+    ProtocolError::Write(Box::new(e.into()))
+}
+
+fn read(e: impl Into<redb::Error>) -> ProtocolError {
+    ProtocolError::Read(Box::new(e.into()))
+}
+
+fn decode(bytes: &[u8], id: &str, seq: i64) -> Result<Payload, ProtocolError> {
+    record::decode_record(bytes)
+        .ok_or_else(|| ProtocolError::Read(Box::new(CorruptedPayload { id: id.to_string(), seq })))
+}
+
+impl RedbJournal {
+    /// Open the database at `path`, creating it if missing.
+    pub fn open(path: impl AsRef<Path>) -> Result<RedbJournal, ProtocolError> {
+        Self::new(Database::create(path).map_err(setup)?)
+    }
+
+    pub fn new(db: Database) -> Result<RedbJournal, ProtocolError> {
+        let txn = db.begin_write().map_err(setup)?;
+        txn.open_table(EVENTS).map_err(setup)?;
+        txn.open_table(LOG).map_err(setup)?;
+        txn.commit().map_err(setup)?;
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    pub async fn latest_sequence(&self, id: &EntityId) -> Result<Option<i64>, ProtocolError> {
+        let id = id.clone();
+        self.blocking(ProtocolError::Read, move |journal| {
+            let txn = journal.db.begin_read().map_err(read)?;
+            let events = txn.open_table(EVENTS).map_err(read)?;
+            latest(&events, id.as_ref()).map_err(read)
+        }).await
+    }
+
+    /// Up to `limit` payloads of every aggregate from global position `from`, in write order.
+    pub async fn since(&self, from: u64, limit: usize) -> Result<Vec<(u64, Payload)>, ProtocolError> {
+        self.blocking(ProtocolError::Read, move |journal| {
+            let txn = journal.db.begin_read().map_err(read)?;
+            let log = txn.open_table(LOG).map_err(read)?;
+            let events = txn.open_table(EVENTS).map_err(read)?;
+            let mut payloads = Vec::new();
+            for entry in log.range(from..).map_err(read)?.take(limit) {
+                let (position, key) = entry.map_err(read)?;
+                let (id, seq) = key.value();
+                let bytes = events.get((id, seq)).map_err(read)?
+                    .ok_or_else(|| ProtocolError::Read(Box::new(NotFound { id: id.to_string(), seq })))?;
+                payloads.push((position.value(), decode(bytes.value(), id, seq)?));
+            }
+            Ok(payloads)
+        }).await
+    }
+
+    /// Run `f` on the database from a blocking thread, reporting a failure to run it with `fail`.
+    async fn blocking<R: Send + 'static>(
+        &self,
+        fail: fn(Box<dyn std::error::Error + Sync + Send>) -> ProtocolError,
+        f: impl FnOnce(&RedbJournal) -> Result<R, ProtocolError> + Send + 'static,
+    ) -> Result<R, ProtocolError> {
+        let journal = self.clone();
+        match tokio::task::spawn_blocking(move || f(&journal)).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(fail(Box::new(e))),
+        }
+    }
+
+    fn append(&self, payloads: &[Payload]) -> Result<(), ProtocolError> {
+        let txn = self.db.begin_write().map_err(write)?;
+        {
+            let mut events = txn.open_table(EVENTS).map_err(write)?;
+            let mut log = txn.open_table(LOG).map_err(write)?;
+            let next = log.last().map_err(write)?.map_or(0, |(position, _)| position.value() + 1);
+            let mut bytes = Vec::new();
+            for (position, payload) in (next..).zip(payloads) {
+                if latest(&events, &payload.id).map_err(write)?.is_some_and(|latest| payload.sequence_id <= latest) {
+                    // Dropping the transaction without committing aborts it.
+                    return Err(ProtocolError::Conflict { id: payload.id.clone(), seq: payload.sequence_id });
+                }
+                bytes.clear();
+                record::encode(payload, &mut bytes);
+                events.insert((payload.id.as_str(), payload.sequence_id), bytes.as_slice()).map_err(write)?;
+                log.insert(position, (payload.id.as_str(), payload.sequence_id)).map_err(write)?;
+            }
+        }
+        txn.commit().map_err(write)
+    }
+}
+
+fn latest(events: &impl ReadableTable<(&'static str, i64), &'static [u8]>, id: &str) -> Result<Option<i64>, redb::StorageError> {
+    let last = events.range((id, i64::MIN)..=(id, i64::MAX))?.next_back().transpose()?;
+    Ok(last.map(|(key, _)| key.value().1))
+}
+
+#[async_trait]
+impl Writer for RedbJournal {
+    async fn write(&self, _: EntityId, payload: Payload) -> Result<(), ProtocolError> {
+        self.blocking(ProtocolError::Write, move |journal| journal.append(std::slice::from_ref(&payload))).await
+    }
+
+    async fn write_batch(&self, _: EntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        self.blocking(ProtocolError::Write, move |journal| journal.append(&payloads)).await
+    }
+}
+
+#[async_trait]
+impl Reader for RedbJournal {
+    async fn read(&self, id: EntityId, seq: i64) -> Result<Payload, ProtocolError> {
+        self.blocking(ProtocolError::Read, move |journal| {
+            let txn = journal.db.begin_read().map_err(read)?;
+            let events = txn.open_table(EVENTS).map_err(read)?;
+            let bytes = events.get((id.as_ref(), seq)).map_err(read)?
+                .ok_or_else(|| ProtocolError::Read(Box::new(NotFound { id: id.to_string(), seq })))?;
+            decode(bytes.value(), id.as_ref(), seq)
+        }).await
+    }
+
+    async fn read_to(&self, id: EntityId, from: i64, to: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
+        if from > to {
+            return Ok(BTreeSet::new());
+        }
+        self.blocking(ProtocolError::Read, move |journal| {
+            let txn = journal.db.begin_read().map_err(read)?;
+            let events = txn.open_table(EVENTS).map_err(read)?;
+            let mut payloads = BTreeSet::new();
+            for entry in events.range((id.as_ref(), from)..=(id.as_ref(), to)).map_err(read)? {
+                let (key, bytes) = entry.map_err(read)?;
+                payloads.insert(decode(bytes.value(), id.as_ref(), key.value().1)?);
+            }
+            Ok(payloads)
+        }).await
+    }
+}
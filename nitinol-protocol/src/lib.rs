@@ -1,6 +1,25 @@
 pub mod io;
 pub mod errors;
+pub mod codec;
 
 mod payload;
 
+#[cfg(any(feature = "file", feature = "redb"))]
+mod record;
+
+#[cfg(feature = "inmemory")]
+pub mod inmemory;
+
+#[cfg(feature = "file")]
+pub mod file;
+
+#[cfg(feature = "redb")]
+pub mod kv;
+
+#[cfg(feature = "encryption")]
+pub mod crypto;
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub mod sql;
+
 pub use self::payload::*;
\ No newline at end of file
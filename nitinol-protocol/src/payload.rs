@@ -1,12 +1,16 @@
 use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
 use time::OffsetDateTime;
+use nitinol_core::command::CommandId;
 use nitinol_core::errors::{DeserializeError, SerializeError};
 use nitinol_core::event::Event;
 use nitinol_core::identifier::EntityId;
 
 /// Basic format of the data to be saved.
+///
+/// Adaptors reading payloads back from storage build them with [`Payload::from_parts`].
 #[derive(Clone)]
+#[non_exhaustive]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 pub struct Payload {
     /// Aggregate entity identifier
@@ -18,7 +22,13 @@ pub struct Payload {
     /// Data body in binary format
     pub bytes: Vec<u8>,
     /// Time the Event was generated
-    pub created_at: OffsetDateTime
+    pub created_at: OffsetDateTime,
+    /// Deduplication key of the command that produced the Event, if any
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub command_id: Option<String>,
+    /// Name of the [`Codec`](crate::codec::Codec) `bytes` are compressed with, if any
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub codec: Option<String>
 }
 
 impl Payload {
@@ -26,12 +36,34 @@ impl Payload {
         Ok(Self {
             id: aggregate_id.to_string(),
             sequence_id: seq,
-            registry_key: E::EVENT_TYPE.to_string(),
+            registry_key: event.event_type().to_string(),
             bytes: event.as_bytes()?,
-            created_at: OffsetDateTime::now_utc()
+            created_at: OffsetDateTime::now_utc(),
+            command_id: None,
+            codec: None
         })
     }
     
+    /// Payload of already serialized event `bytes`, such as a row read back by a storage adaptor.
+    ///
+    /// `command_id` and `codec` are unset and can be assigned afterwards.
+    pub fn from_parts(id: impl Into<String>, sequence_id: i64, registry_key: impl Into<String>, bytes: Vec<u8>, created_at: OffsetDateTime) -> Self {
+        Self {
+            id: id.into(),
+            sequence_id,
+            registry_key: registry_key.into(),
+            bytes,
+            created_at,
+            command_id: None,
+            codec: None
+        }
+    }
+    
+    pub fn with_command_id(mut self, command_id: Option<&CommandId>) -> Self {
+        self.command_id = command_id.map(ToString::to_string);
+        self
+    }
+    
     pub fn to_event<E: Event>(&self) -> Result<E, DeserializeError> {
         E::from_bytes(&self.bytes)
     }
@@ -44,6 +76,8 @@ impl Debug for Payload {
             .field("sequence", &self.sequence_id)
             .field("bytes", &format!("<{} bytes>", self.bytes.len()))
             .field("created_at", &self.created_at)
+            .field("command_id", &self.command_id)
+            .field("codec", &self.codec)
             .finish()
     }
 }
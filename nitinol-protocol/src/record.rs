@@ -0,0 +1,138 @@
+use time::OffsetDateTime;
+
+use crate::Payload;
+
+/// Length and checksum of the body, preceding every record.
+pub(crate) const HEADER: usize = 8;
+
+/// Set in the length of a header framing a whole batch, whose body is the records of the batch.
+#[cfg(feature = "file")]
+pub(crate) const BATCH: u32 = 1 << 31;
+
+/// Encode `payload` as `[body length: u32][crc32 of body: u32][body]`, little-endian.
+pub(crate) fn encode(payload: &Payload, buf: &mut Vec<u8>) {
+    let mut body = Vec::with_capacity(payload.id.len() + payload.registry_key.len() + payload.bytes.len() + 48);
+    put_str(&mut body, &payload.id);
+    body.extend_from_slice(&payload.sequence_id.to_le_bytes());
+    put_str(&mut body, &payload.registry_key);
+    body.extend_from_slice(&payload.created_at.unix_timestamp_nanos().to_le_bytes());
+    match &payload.command_id {
+        Some(command_id) => {
+            body.push(1);
+            put_str(&mut body, command_id);
+        }
+        None => body.push(0),
+    }
+    body.extend_from_slice(&(payload.bytes.len() as u32).to_le_bytes());
+    body.extend_from_slice(&payload.bytes);
+    // Trailing, so that records written before codecs existed still decode.
+    if let Some(codec) = &payload.codec {
+        put_str(&mut body, codec);
+    }
+
+    buf.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&crc32(&body).to_le_bytes());
+    buf.extend_from_slice(&body);
+}
+
+/// Encode `payloads` as one [`BATCH`] frame checksummed as a whole, so that it is either read back entirely or not at all.
+///
+/// A single payload is encoded as a plain record. Returns the offset in `buf` of the record of each payload.
+#[cfg(feature = "file")]
+pub(crate) fn encode_batch(payloads: &[Payload], buf: &mut Vec<u8>) -> Vec<u64> {
+    if let [payload] = payloads {
+        let offset = buf.len() as u64;
+        encode(payload, buf);
+        return vec![offset];
+    }
+    let start = buf.len();
+    buf.extend_from_slice(&[0; HEADER]);
+    let offsets = payloads.iter()
+        .map(|payload| {
+            let offset = buf.len() as u64;
+            encode(payload, buf);
+            offset
+        })
+        .collect();
+    let len = (buf.len() - start - HEADER) as u32 | BATCH;
+    let crc = crc32(&buf[start + HEADER..]);
+    buf[start..start + 4].copy_from_slice(&len.to_le_bytes());
+    buf[start + 4..start + HEADER].copy_from_slice(&crc.to_le_bytes());
+    offsets
+}
+
+/// Body length and checksum read from a record header.
+pub(crate) fn header(bytes: &[u8; HEADER]) -> (usize, u32) {
+    let len = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+    let crc = u32::from_le_bytes(bytes[4..].try_into().unwrap());
+    (len, crc)
+}
+
+/// Decode a whole record, header included.
+#[cfg(feature = "redb")]
+pub(crate) fn decode_record(bytes: &[u8]) -> Option<Payload> {
+    let (len, crc) = header(bytes.get(..HEADER)?.try_into().ok()?);
+    decode(bytes.get(HEADER..HEADER + len)?, crc)
+}
+
+/// Decode a body whose checksum is `crc`, `None` if it is torn or corrupted.
+pub(crate) fn decode(body: &[u8], crc: u32) -> Option<Payload> {
+    if crc32(body) != crc {
+        return None;
+    }
+    let mut cursor = Cursor(body);
+    let id = cursor.str()?;
+    let sequence_id = i64::from_le_bytes(cursor.array()?);
+    let registry_key = cursor.str()?;
+    let created_at = OffsetDateTime::from_unix_timestamp_nanos(i128::from_le_bytes(cursor.array()?)).ok()?;
+    let command_id = match cursor.take(1)?[0] {
+        0 => None,
+        _ => Some(cursor.str()?),
+    };
+    let len = u32::from_le_bytes(cursor.array()?) as usize;
+    let bytes = cursor.take(len)?.to_vec();
+    let codec = match cursor.0.is_empty() {
+        true => None,
+        false => Some(cursor.str()?),
+    };
+    Some(Payload { id, sequence_id, registry_key, bytes, created_at, command_id, codec })
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buf.extend_from_slice(value.as_bytes());
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = u32::from_le_bytes(self.array()?) as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
+
+/// CRC-32 (IEEE), computed bitwise as records are small.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
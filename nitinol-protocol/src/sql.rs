@@ -0,0 +1,29 @@
+//! Journals stored in a SQL database through sqlx.
+//!
+//! Events are kept in a single `journal` table created by the embedded migrations.
+//! Its primary key `(id, sequence_id)` makes every event unique within its aggregate,
+//! and also backs every read, which look up one aggregate and a range of its sequence.
+//! No other index is created, as nothing queries across aggregates.
+//!
+//! Like [`InMemoryEventStore`](crate::inmemory::InMemoryEventStore), a write at or below
+//! the latest sequence of its aggregate fails with [`ProtocolError::Conflict`].
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+use crate::errors::ProtocolError;
+use crate::Payload;
+
+fn write_error(payload: &Payload, error: sqlx::Error) -> ProtocolError {
+    match error {
+        sqlx::Error::Database(e) if e.is_unique_violation() => conflict(payload),
+        e => ProtocolError::Write(Box::new(e)),
+    }
+}
+
+fn conflict(payload: &Payload) -> ProtocolError {
+    ProtocolError::Conflict { id: payload.id.clone(), seq: payload.sequence_id }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use nitinol_core::identifier::EntityId;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Sqlite, SqliteExecutor, SqlitePool, Transaction};
+
+use crate::errors::{NotFound, ProtocolError};
+use crate::io::{Reader, Writer};
+use crate::Payload;
+
+use super::{conflict, write_error};
+
+/// Journal stored in a SQLite database.
+#[derive(Debug, Clone)]
+pub struct SqliteJournal {
+    pool: SqlitePool,
+}
+
+impl SqliteJournal {
+    /// Use `pool`, which must already be migrated, see [`SqliteJournal::migrate`].
+    pub fn new(pool: SqlitePool) -> SqliteJournal {
+        Self { pool }
+    }
+
+    /// Connect to `url`, creating the database if missing, and run the migrations.
+    pub async fn connect(url: &str) -> Result<SqliteJournal, ProtocolError> {
+        let options = url.parse::<SqliteConnectOptions>()
+            .map_err(|e| ProtocolError::Setup(Box::new(e)))?
+            .create_if_missing(true);
+        // Every connection to an in-memory database opens a distinct one.
+        let connections = if url.contains(":memory:") { 1 } else { 8 };
+        let pool = SqlitePoolOptions::new()
+            .max_connections(connections)
+            .connect_with(options)
+            .await
+            .map_err(|e| ProtocolError::Setup(Box::new(e)))?;
+        let journal = Self::new(pool);
+        journal.migrate().await?;
+        Ok(journal)
+    }
+
+    pub async fn migrate(&self) -> Result<(), ProtocolError> {
+        sqlx::migrate!("./migrations/sqlite")
+            .run(&self.pool)
+            .await
+            .map_err(|e| ProtocolError::Setup(Box::new(e)))
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+async fn insert<'a>(executor: impl SqliteExecutor<'a>, payload: &Payload) -> Result<(), ProtocolError> {
+    // language=sqlite
+    let written = sqlx::query(r#"
+        INSERT INTO journal (id, sequence_id, registry_key, bytes, created_at, command_id, codec)
+        SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7
+        WHERE NOT EXISTS (SELECT 1 FROM journal WHERE id = ?1 AND sequence_id >= ?2)
+    "#)
+        .bind(&payload.id)
+        .bind(payload.sequence_id)
+        .bind(&payload.registry_key)
+        .bind(&payload.bytes)
+        .bind(payload.created_at)
+        .bind(&payload.command_id)
+        .bind(&payload.codec)
+        .execute(executor)
+        .await
+        .map_err(|e| write_error(payload, e))?;
+    if written.rows_affected() == 0 {
+        return Err(conflict(payload));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Writer for SqliteJournal {
+    async fn write(&self, _: EntityId, payload: Payload) -> Result<(), ProtocolError> {
+        insert(&self.pool, &payload).await
+    }
+
+    async fn write_batch(&self, _: EntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        let mut transaction: Transaction<'_, Sqlite> = self.pool.begin().await
+            .map_err(|e| ProtocolError::Write(Box::new(e)))?;
+        for payload in &payloads {
+            insert(&mut *transaction, payload).await?;
+        }
+        transaction.commit().await
+            .map_err(|e| ProtocolError::Write(Box::new(e)))
+    }
+}
+
+#[async_trait]
+impl Reader for SqliteJournal {
+    async fn read(&self, id: EntityId, seq: i64) -> Result<Payload, ProtocolError> {
+        // language=sqlite
+        sqlx::query_as::<_, Payload>(r#"
+            SELECT id, sequence_id, registry_key, bytes, created_at, command_id, codec
+            FROM journal
+            WHERE id = ? AND sequence_id = ?
+        "#)
+            .bind(id.as_ref())
+            .bind(seq)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ProtocolError::Read(Box::new(e)))?
+            .ok_or_else(|| ProtocolError::Read(Box::new(NotFound { id: id.to_string(), seq })))
+    }
+
+    async fn read_to(&self, id: EntityId, from: i64, to: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
+        // language=sqlite
+        let payloads = sqlx::query_as::<_, Payload>(r#"
+            SELECT id, sequence_id, registry_key, bytes, created_at, command_id, codec
+            FROM journal
+            WHERE id = ? AND sequence_id BETWEEN ? AND ?
+            ORDER BY sequence_id
+        "#)
+            .bind(id.as_ref())
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ProtocolError::Read(Box::new(e)))?;
+        Ok(payloads.into_iter().collect())
+    }
+}
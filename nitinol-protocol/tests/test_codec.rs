@@ -0,0 +1,92 @@
+#![cfg(all(feature = "inmemory", feature = "zstd", feature = "lz4"))]
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::ToEntityId;
+use nitinol_protocol::codec::{Lz4, Zstd};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::inmemory::InMemoryEventStore;
+use nitinol_protocol::io::{ReadProtocol, WriteProtocol};
+
+#[derive(Debug, PartialEq)]
+pub struct Described(String);
+
+impl Event for Described {
+    const EVENT_TYPE: &'static str = "described";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.as_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Described(String::from_utf8(bytes.to_vec()).unwrap()))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ticked(u8);
+
+impl Event for Ticked {
+    const EVENT_TYPE: &'static str = "ticked";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(vec![self.0])
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Ticked(bytes[0]))
+    }
+}
+
+fn described() -> Described {
+    Described(r#"{"description":"repeated text"}"#.repeat(64))
+}
+
+#[tokio::test]
+async fn compressed_payloads_are_read_back() {
+    let store = InMemoryEventStore::new();
+    let id = "doc".to_entity_id();
+    let writer = WriteProtocol::new(store.clone()).with_compression(Zstd::default());
+    writer.write(id.clone(), &described(), 0).await.unwrap();
+
+    let stored = &store.payloads(&id)[0];
+    assert_eq!(stored.codec.as_deref(), Some("zstd"));
+    assert!(stored.bytes.len() < described().as_bytes().unwrap().len());
+
+    let reader = ReadProtocol::new(store).with_codec(Zstd::default());
+    assert_eq!(reader.read::<Described>(id.clone(), 0).await.unwrap(), described());
+    let payload = reader.read_to_latest(id, 0).await.unwrap().pop_first().unwrap();
+    assert_eq!(payload.codec, None);
+    assert_eq!(payload.to_event::<Described>().unwrap(), described());
+}
+
+#[tokio::test]
+async fn codec_is_chosen_per_event_type() {
+    let store = InMemoryEventStore::new();
+    let id = "doc".to_entity_id();
+    let writer = WriteProtocol::new(store.clone())
+        .with_compression(Zstd::default())
+        .with_compression_for::<Described>(Lz4)
+        .without_compression_for::<Ticked>();
+    writer.write(id.clone(), &described(), 0).await.unwrap();
+    writer.write(id.clone(), &Ticked(7), 1).await.unwrap();
+
+    let codecs = store.payloads(&id).into_iter().map(|payload| payload.codec).collect::<Vec<_>>();
+    assert_eq!(codecs, vec![Some("lz4".to_string()), None]);
+
+    let reader = ReadProtocol::new(store).with_codec(Lz4);
+    assert_eq!(reader.read::<Described>(id.clone(), 0).await.unwrap(), described());
+    assert_eq!(reader.read::<Ticked>(id, 1).await.unwrap(), Ticked(7));
+}
+
+#[tokio::test]
+async fn uncompressed_payloads_are_read_as_they_are() {
+    let store = InMemoryEventStore::new();
+    let id = "doc".to_entity_id();
+    WriteProtocol::new(store.clone()).write(id.clone(), &described(), 0).await.unwrap();
+    WriteProtocol::new(store.clone()).with_compression(Lz4).write(id.clone(), &described(), 1).await.unwrap();
+
+    let reader = ReadProtocol::new(store.clone()).with_codec(Zstd::default());
+    assert_eq!(reader.read::<Described>(id.clone(), 0).await.unwrap(), described());
+    assert!(matches!(reader.read::<Described>(id, 1).await, Err(ProtocolError::Read(_))));
+}
@@ -0,0 +1,138 @@
+#![cfg(all(feature = "inmemory", feature = "encryption", feature = "zstd"))]
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::ToEntityId;
+use nitinol_protocol::codec::Zstd;
+use nitinol_protocol::crypto::{Encryption, InMemoryKeyStore, KeyStore};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::inmemory::InMemoryEventStore;
+use nitinol_protocol::io::{ReadProtocol, WriteProtocol};
+
+#[derive(Debug, PartialEq)]
+pub struct Registered {
+    email: String,
+}
+
+impl Event for Registered {
+    const EVENT_TYPE: &'static str = "registered";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.email.as_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Registered { email: String::from_utf8(bytes.to_vec()).unwrap() })
+    }
+}
+
+fn registered(email: &str) -> Registered {
+    Registered { email: email.to_string() }
+}
+
+#[tokio::test]
+async fn encrypted_payloads_are_read_back() {
+    let store = InMemoryEventStore::new();
+    let encryption = Encryption::new(InMemoryKeyStore::default());
+    let id = "user-1".to_entity_id();
+    let writer = WriteProtocol::new(store.clone()).with_encryption(encryption.clone());
+    writer.write(id.clone(), &registered("alice@example.com"), 0).await.unwrap();
+
+    let stored = &store.payloads(&id)[0];
+    assert_eq!(stored.codec.as_deref(), Some("aes-256-gcm"));
+    assert!(!stored.bytes.windows(5).any(|window| window == b"alice"));
+
+    let reader = ReadProtocol::new(store).with_encryption(encryption);
+    assert_eq!(reader.read::<Registered>(id.clone(), 0).await.unwrap(), registered("alice@example.com"));
+    let payload = reader.read_to_latest(id, 0).await.unwrap().pop_first().unwrap();
+    assert_eq!(payload.codec, None);
+}
+
+#[tokio::test]
+async fn payloads_are_encrypted_after_compression() {
+    let store = InMemoryEventStore::new();
+    let encryption = Encryption::new(InMemoryKeyStore::default());
+    let id = "user-1".to_entity_id();
+    let writer = WriteProtocol::new(store.clone())
+        .with_compression(Zstd::default())
+        .with_encryption(encryption.clone());
+    writer.write(id.clone(), &registered(&"a".repeat(256)), 0).await.unwrap();
+    assert_eq!(store.payloads(&id)[0].codec.as_deref(), Some("zstd+aes-256-gcm"));
+
+    let reader = ReadProtocol::new(store)
+        .with_codec(Zstd::default())
+        .with_encryption(encryption);
+    assert_eq!(reader.read::<Registered>(id, 0).await.unwrap(), registered(&"a".repeat(256)));
+}
+
+#[tokio::test]
+async fn shredded_payloads_are_reported() {
+    let store = InMemoryEventStore::new();
+    let encryption = Encryption::new(InMemoryKeyStore::default());
+    let (shredded, kept) = ("user-1".to_entity_id(), "user-2".to_entity_id());
+    let writer = WriteProtocol::new(store.clone()).with_encryption(encryption.clone());
+    writer.write(shredded.clone(), &registered("alice@example.com"), 0).await.unwrap();
+    writer.write(kept.clone(), &registered("bob@example.com"), 0).await.unwrap();
+
+    encryption.shred(&shredded).await.unwrap();
+
+    let reader = ReadProtocol::new(store).with_encryption(encryption.clone());
+    let error = reader.read_to_latest(shredded.clone(), 0).await.unwrap_err();
+    assert!(matches!(error, ProtocolError::Shredded { id, seq: 0 } if id == "user-1"));
+    assert!(reader.read::<Registered>(kept, 0).await.is_ok());
+
+    // A shredded key is never created again.
+    let error = writer.write(shredded, &registered("alice@example.com"), 1).await.unwrap_err();
+    assert!(matches!(error, ProtocolError::Write(_)));
+}
+
+#[tokio::test]
+async fn aggregates_can_share_a_subject() {
+    let store = InMemoryEventStore::new();
+    let keys = InMemoryKeyStore::default();
+    let encryption = Encryption::new(keys.clone())
+        .with_subject(|id| id.as_ref().split('/').next().unwrap().to_string());
+    let (profile, orders) = ("alice/profile".to_entity_id(), "alice/orders".to_entity_id());
+    let writer = WriteProtocol::new(store.clone()).with_encryption(encryption.clone());
+    writer.write(profile.clone(), &registered("alice@example.com"), 0).await.unwrap();
+    writer.write(orders.clone(), &registered("alice@example.com"), 0).await.unwrap();
+    assert!(keys.get("alice").await.unwrap().is_some());
+
+    encryption.shred(&profile).await.unwrap();
+
+    let reader = ReadProtocol::new(store).with_encryption(encryption);
+    assert!(matches!(reader.read::<Registered>(profile, 0).await, Err(ProtocolError::Shredded { .. })));
+    assert!(matches!(reader.read::<Registered>(orders, 0).await, Err(ProtocolError::Shredded { .. })));
+}
+
+#[tokio::test]
+async fn tampered_payloads_are_rejected() {
+    let store = InMemoryEventStore::new();
+    let encryption = Encryption::new(InMemoryKeyStore::default());
+    let id = "user-1".to_entity_id();
+    let writer = WriteProtocol::new(store.clone()).with_encryption(encryption.clone());
+    writer.write(id.clone(), &registered("alice@example.com"), 0).await.unwrap();
+
+    // Replaying the ciphertext under another sequence fails authentication.
+    let mut moved = store.payloads(&id)[0].clone();
+    moved.sequence_id = 1;
+    WriteProtocol::new(store.clone()).write_batch(id.clone(), vec![moved]).await.unwrap();
+
+    let reader = ReadProtocol::new(store).with_encryption(encryption);
+    assert!(reader.read::<Registered>(id.clone(), 0).await.is_ok());
+    assert!(matches!(reader.read::<Registered>(id, 1).await, Err(ProtocolError::Read(_))));
+}
+
+#[tokio::test]
+async fn encrypted_payloads_need_the_encryption() {
+    let store = InMemoryEventStore::new();
+    let id = "user-1".to_entity_id();
+    WriteProtocol::new(store.clone())
+        .with_encryption(Encryption::new(InMemoryKeyStore::default()))
+        .write(id.clone(), &registered("alice@example.com"), 0)
+        .await
+        .unwrap();
+
+    let error = ReadProtocol::new(store).read::<Registered>(id, 0).await.unwrap_err();
+    assert!(matches!(error, ProtocolError::Read(_)));
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "file")]
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::file::{FileJournal, FileOptions, FsyncPolicy};
+use nitinol_protocol::io::{Reader, Writer};
+use nitinol_protocol::Payload;
+
+pub struct Ticked(i64);
+
+impl Event for Ticked {
+    const EVENT_TYPE: &'static str = "ticked";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Ticked(i64::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+fn payload(id: &EntityId, seq: i64) -> Payload {
+    Payload::new(id.clone(), seq, &Ticked(seq)).unwrap()
+}
+
+fn segments(dir: &Path) -> Vec<String> {
+    let mut names = fs::read_dir(dir).unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn payloads_survive_reopening() {
+    let dir = tempfile::tempdir().unwrap();
+    let id = "clock".to_entity_id();
+    {
+        let journal = FileJournal::open(dir.path()).unwrap();
+        for seq in 0..3 {
+            journal.write(id.clone(), payload(&id, seq)).await.unwrap();
+        }
+    }
+
+    let journal = FileJournal::open(dir.path()).unwrap();
+    let read = journal.read_to_latest(id.clone(), 1).await.unwrap();
+    assert_eq!(read.iter().map(|payload| payload.to_event::<Ticked>().unwrap().0).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(journal.latest_sequence(&id), Some(2));
+    assert!(matches!(journal.write(id.clone(), payload(&id, 2)).await, Err(ProtocolError::Conflict { seq: 2, .. })));
+}
+
+#[tokio::test]
+async fn codec_marker_is_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let id = "clock".to_entity_id();
+    {
+        let journal = FileJournal::open(dir.path()).unwrap();
+        journal.write(id.clone(), payload(&id, 0)).await.unwrap();
+        let mut compressed = payload(&id, 1);
+        compressed.codec = Some("lz4".to_string());
+        journal.write(id.clone(), compressed).await.unwrap();
+    }
+
+    let journal = FileJournal::open(dir.path()).unwrap();
+    let codecs = journal.read_to_latest(id, 0).await.unwrap().into_iter().map(|payload| payload.codec).collect::<Vec<_>>();
+    assert_eq!(codecs, vec![None, Some("lz4".to_string())]);
+}
+
+#[tokio::test]
+async fn segments_roll_over() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = FileOptions::default().with_segment_size(128).with_fsync(FsyncPolicy::Never);
+    let journal = FileJournal::open_with(dir.path(), options).unwrap();
+    let id = "clock".to_entity_id();
+    for seq in 0..10 {
+        journal.write(id.clone(), payload(&id, seq)).await.unwrap();
+    }
+    journal.sync().await.unwrap();
+
+    assert!(journal.segments() > 1);
+    assert_eq!(segments(dir.path()).len(), journal.segments());
+    assert_eq!(journal.read(id.clone(), 0).await.unwrap().to_event::<Ticked>().unwrap().0, 0);
+    assert_eq!(journal.read_to_latest(id, 0).await.unwrap().len(), 10);
+}
+
+#[tokio::test]
+async fn torn_tail_is_truncated() {
+    let dir = tempfile::tempdir().unwrap();
+    let id = "clock".to_entity_id();
+    {
+        let journal = FileJournal::open(dir.path()).unwrap();
+        journal.write(id.clone(), payload(&id, 0)).await.unwrap();
+        journal.write(id.clone(), payload(&id, 1)).await.unwrap();
+    }
+    let last = dir.path().join(segments(dir.path()).pop().unwrap());
+    let len = fs::metadata(&last).unwrap().len();
+    // Simulate a crash in the middle of writing a third record.
+    OpenOptions::new().append(true).open(&last).unwrap().write_all(&[42, 0, 0, 0, 1, 2]).unwrap();
+
+    let journal = FileJournal::open(dir.path()).unwrap();
+    assert_eq!(fs::metadata(&last).unwrap().len(), len);
+    assert_eq!(journal.latest_sequence(&id), Some(1));
+    journal.write(id.clone(), payload(&id, 2)).await.unwrap();
+    assert_eq!(journal.read_to_latest(id, 0).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn corrupted_sealed_segment_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = FileOptions::default().with_segment_size(1);
+    let id = "clock".to_entity_id();
+    {
+        let journal = FileJournal::open_with(dir.path(), options.clone()).unwrap();
+        journal.write(id.clone(), payload(&id, 0)).await.unwrap();
+        journal.write(id.clone(), payload(&id, 1)).await.unwrap();
+    }
+    let first = dir.path().join(&segments(dir.path())[0]);
+    let mut bytes = fs::read(&first).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    fs::write(&first, bytes).unwrap();
+
+    assert!(matches!(FileJournal::open_with(dir.path(), options), Err(ProtocolError::Setup(_))));
+}
+
+#[tokio::test]
+async fn torn_batch_is_dropped_as_a_whole() {
+    let dir = tempfile::tempdir().unwrap();
+    let id = "clock".to_entity_id();
+    {
+        let journal = FileJournal::open(dir.path()).unwrap();
+        journal.write(id.clone(), payload(&id, 0)).await.unwrap();
+    }
+    let last = dir.path().join(segments(dir.path()).pop().unwrap());
+    let len = fs::metadata(&last).unwrap().len();
+    {
+        let journal = FileJournal::open(dir.path()).unwrap();
+        journal.write_batch(id.clone(), (1..4).map(|seq| payload(&id, seq)).collect()).await.unwrap();
+    }
+    assert_eq!(FileJournal::open(dir.path()).unwrap().latest_sequence(&id), Some(3));
+
+    // Simulate a crash while writing the last record of the batch.
+    let full = fs::metadata(&last).unwrap().len();
+    OpenOptions::new().write(true).open(&last).unwrap().set_len(full - 4).unwrap();
+
+    let journal = FileJournal::open(dir.path()).unwrap();
+    assert_eq!(fs::metadata(&last).unwrap().len(), len);
+    assert_eq!(journal.latest_sequence(&id), Some(0));
+}
+
+#[tokio::test]
+async fn compaction_drops_payloads_of_sealed_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = FileOptions::default().with_segment_size(1);
+    let journal = FileJournal::open_with(dir.path(), options.clone()).unwrap();
+    let (a, b) = ("a".to_entity_id(), "b".to_entity_id());
+    for seq in 0..3 {
+        journal.write(a.clone(), payload(&a, seq)).await.unwrap();
+        journal.write(b.clone(), payload(&b, seq)).await.unwrap();
+    }
+    assert_eq!(journal.segments(), 6);
+
+    let dropped = journal.compact(|payload| payload.id != "a" || payload.sequence_id == 2).await.unwrap();
+    assert_eq!(dropped, 2);
+    assert_eq!(journal.segments(), 4);
+    assert_eq!(journal.read_to_latest(a.clone(), 0).await.unwrap().len(), 1);
+    assert_eq!(journal.read_to_latest(b.clone(), 0).await.unwrap().len(), 3);
+
+    let reopened = FileJournal::open_with(dir.path(), options).unwrap();
+    assert_eq!(reopened.latest_sequence(&a), Some(2));
+    assert!(matches!(reopened.write(a.clone(), payload(&a, 1)).await, Err(ProtocolError::Conflict { seq: 1, .. })));
+    assert_eq!(reopened.read_to_latest(b, 0).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn compaction_keeps_latest_payloads() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = FileOptions::default().with_segment_size(1);
+    let journal = FileJournal::open_with(dir.path(), options).unwrap();
+    let (a, b) = ("a".to_entity_id(), "b".to_entity_id());
+    for seq in 0..3 {
+        journal.write(a.clone(), payload(&a, seq)).await.unwrap();
+        journal.write(b.clone(), payload(&b, seq)).await.unwrap();
+    }
+
+    let rejected = journal.compact(|payload| payload.id != "a").await;
+    assert!(matches!(rejected, Err(ProtocolError::Conflict { seq: 2, .. })));
+    assert_eq!(journal.segments(), 6);
+    assert_eq!(journal.read_to_latest(a, 0).await.unwrap().len(), 3);
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "inmemory")]
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::inmemory::InMemoryEventStore;
+use nitinol_protocol::io::{Reader, Writer};
+use nitinol_protocol::Payload;
+
+pub struct Ticked(i64);
+
+impl Event for Ticked {
+    const EVENT_TYPE: &'static str = "ticked";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Ticked(i64::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+fn payload(id: &EntityId, seq: i64) -> Payload {
+    Payload::new(id.clone(), seq, &Ticked(seq)).unwrap()
+}
+
+#[tokio::test]
+async fn reads_are_inclusive_and_ordered() {
+    let store = InMemoryEventStore::new();
+    let id = "clock".to_entity_id();
+    for seq in 0..5 {
+        store.write(id.clone(), payload(&id, seq)).await.unwrap();
+    }
+
+    let read = store.read_to(id.clone(), 1, 3).await.unwrap();
+    assert_eq!(read.iter().map(|payload| payload.sequence_id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(store.read(id.clone(), 4).await.unwrap().to_event::<Ticked>().unwrap().0, 4);
+    assert!(matches!(store.read(id.clone(), 5).await, Err(ProtocolError::Read(_))));
+    assert_eq!(store.latest_sequence(&id), Some(4));
+}
+
+#[tokio::test]
+async fn sequence_only_grows() {
+    let store = InMemoryEventStore::new();
+    let id = "clock".to_entity_id();
+    store.write(id.clone(), payload(&id, 0)).await.unwrap();
+    store.write(id.clone(), payload(&id, 2)).await.unwrap();
+
+    let duplicate = store.write(id.clone(), payload(&id, 2)).await;
+    assert!(matches!(duplicate, Err(ProtocolError::Conflict { seq: 2, .. })));
+    let behind = store.write(id.clone(), payload(&id, 1)).await;
+    assert!(matches!(behind, Err(ProtocolError::Conflict { seq: 1, .. })));
+    assert_eq!(store.len(), 2);
+}
+
+#[tokio::test]
+async fn batch_is_atomic() {
+    let store = InMemoryEventStore::new();
+    let id = "clock".to_entity_id();
+    store.write(id.clone(), payload(&id, 3)).await.unwrap();
+
+    let batch = vec![payload(&id, 4), payload(&id, 5), payload(&id, 5)];
+    assert!(store.write_batch(id.clone(), batch).await.is_err());
+    assert_eq!(store.len(), 1);
+
+    store.write_batch(id.clone(), vec![payload(&id, 4), payload(&id, 5)]).await.unwrap();
+    assert_eq!(store.latest_sequence(&id), Some(5));
+}
+
+#[tokio::test]
+async fn log_keeps_global_write_order() {
+    let store = InMemoryEventStore::new();
+    let (a, b) = ("a".to_entity_id(), "b".to_entity_id());
+    store.write(b.clone(), payload(&b, 0)).await.unwrap();
+    store.write(a.clone(), payload(&a, 0)).await.unwrap();
+    store.write(b.clone(), payload(&b, 1)).await.unwrap();
+
+    let log = store.since(1);
+    assert_eq!(log.iter().map(|payload| (payload.id.as_str(), payload.sequence_id)).collect::<Vec<_>>(), vec![("a", 0), ("b", 1)]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_appends_conflict_instead_of_overwriting() {
+    let store = InMemoryEventStore::new();
+    let id = "clock".to_entity_id();
+
+    let writes = (0..16).map(|_| {
+        let (store, id) = (store.clone(), id.clone());
+        tokio::spawn(async move { store.write(id.clone(), payload(&id, 0)).await })
+    });
+    let mut written = 0;
+    for write in writes {
+        if write.await.unwrap().is_ok() {
+            written += 1;
+        }
+    }
+
+    assert_eq!(written, 1);
+    assert_eq!(store.len(), 1);
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "redb")]
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::io::{Reader, Writer};
+use nitinol_protocol::kv::RedbJournal;
+use nitinol_protocol::Payload;
+
+pub struct Ticked(i64);
+
+impl Event for Ticked {
+    const EVENT_TYPE: &'static str = "ticked";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Ticked(i64::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+fn payload(id: &EntityId, seq: i64) -> Payload {
+    Payload::new(id.clone(), seq, &Ticked(seq)).unwrap()
+}
+
+#[tokio::test]
+async fn payloads_are_range_scanned_per_aggregate() {
+    let dir = tempfile::tempdir().unwrap();
+    let journal = RedbJournal::open(dir.path().join("journal.redb")).unwrap();
+    let (a, ab) = ("a".to_entity_id(), "ab".to_entity_id());
+    for seq in 0..4 {
+        journal.write(a.clone(), payload(&a, seq)).await.unwrap();
+        journal.write(ab.clone(), payload(&ab, seq)).await.unwrap();
+    }
+
+    let read = journal.read_to(a.clone(), 1, 2).await.unwrap();
+    assert_eq!(read.iter().map(|payload| (payload.id.as_str(), payload.sequence_id)).collect::<Vec<_>>(), vec![("a", 1), ("a", 2)]);
+    assert_eq!(journal.read(ab.clone(), 3).await.unwrap().to_event::<Ticked>().unwrap().0, 3);
+    assert!(matches!(journal.read(a.clone(), 4).await, Err(ProtocolError::Read(_))));
+    assert_eq!(journal.latest_sequence(&ab).await.unwrap(), Some(3));
+}
+
+#[tokio::test]
+async fn log_is_scanned_in_write_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let journal = RedbJournal::open(dir.path().join("journal.redb")).unwrap();
+    let (a, b) = ("a".to_entity_id(), "b".to_entity_id());
+    journal.write(b.clone(), payload(&b, 0)).await.unwrap();
+    journal.write(a.clone(), payload(&a, 0)).await.unwrap();
+    journal.write(b.clone(), payload(&b, 1)).await.unwrap();
+
+    let log = journal.since(1, 10).await.unwrap();
+    assert_eq!(log.iter().map(|(position, payload)| (*position, payload.id.as_str(), payload.sequence_id)).collect::<Vec<_>>(), vec![(1, "a", 0), (2, "b", 1)]);
+    assert_eq!(journal.since(0, 1).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn conflicting_batch_is_rolled_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("journal.redb");
+    let id = "clock".to_entity_id();
+    {
+        let journal = RedbJournal::open(&path).unwrap();
+        journal.write(id.clone(), payload(&id, 0)).await.unwrap();
+        let batch = vec![payload(&id, 1), payload(&id, 0)];
+        assert!(matches!(journal.write_batch(id.clone(), batch).await, Err(ProtocolError::Conflict { seq: 0, .. })));
+    }
+
+    let journal = RedbJournal::open(&path).unwrap();
+    assert_eq!(journal.latest_sequence(&id).await.unwrap(), Some(0));
+    assert_eq!(journal.since(0, 10).await.unwrap().len(), 1);
+}
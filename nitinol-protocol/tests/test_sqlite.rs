@@ -0,0 +1,93 @@
+#![cfg(feature = "sqlite")]
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::io::{Reader, Writer};
+use nitinol_protocol::sql::sqlite::SqliteJournal;
+use nitinol_protocol::Payload;
+
+pub struct Ticked(i64);
+
+impl Event for Ticked {
+    const EVENT_TYPE: &'static str = "ticked";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Ticked(i64::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+fn payload(id: &EntityId, seq: i64) -> Payload {
+    Payload::new(id.clone(), seq, &Ticked(seq)).unwrap()
+}
+
+#[tokio::test]
+async fn written_payloads_are_read_back() {
+    let journal = SqliteJournal::connect("sqlite::memory:").await.unwrap();
+    let id = "clock".to_entity_id();
+    for seq in 0..5 {
+        journal.write(id.clone(), payload(&id, seq)).await.unwrap();
+    }
+
+    let read = journal.read(id.clone(), 3).await.unwrap();
+    assert_eq!(read.to_event::<Ticked>().unwrap().0, 3);
+    assert_eq!(read.registry_key, "ticked");
+
+    let range = journal.read_to(id.clone(), 1, 3).await.unwrap();
+    assert_eq!(range.iter().map(|payload| payload.sequence_id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert!(matches!(journal.read(id, 9).await, Err(ProtocolError::Read(_))));
+}
+
+#[tokio::test]
+async fn duplicate_sequence_is_a_conflict() {
+    let journal = SqliteJournal::connect("sqlite::memory:").await.unwrap();
+    let id = "clock".to_entity_id();
+    journal.write(id.clone(), payload(&id, 0)).await.unwrap();
+    journal.write(id.clone(), payload(&id, 2)).await.unwrap();
+
+    let duplicate = journal.write(id.clone(), payload(&id, 2)).await;
+    assert!(matches!(duplicate, Err(ProtocolError::Conflict { seq: 2, .. })));
+    let behind = journal.write(id.clone(), payload(&id, 1)).await;
+    assert!(matches!(behind, Err(ProtocolError::Conflict { seq: 1, .. })));
+}
+
+#[tokio::test]
+async fn batch_is_atomic() {
+    let journal = SqliteJournal::connect("sqlite::memory:").await.unwrap();
+    let id = "clock".to_entity_id();
+
+    let batch = vec![payload(&id, 0), payload(&id, 1), payload(&id, 1)];
+    assert!(journal.write_batch(id.clone(), batch).await.is_err());
+    assert!(journal.read_to_latest(id.clone(), 0).await.unwrap().is_empty());
+
+    journal.write_batch(id.clone(), vec![payload(&id, 0), payload(&id, 1)]).await.unwrap();
+    assert_eq!(journal.read_to_latest(id, 0).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn command_id_is_stored() {
+    let journal = SqliteJournal::connect("sqlite::memory:").await.unwrap();
+    let id = "clock".to_entity_id();
+    let command_id = nitinol_core::command::CommandId::new("tick-0");
+    journal.write(id.clone(), payload(&id, 0).with_command_id(Some(&command_id))).await.unwrap();
+
+    assert_eq!(journal.read(id, 0).await.unwrap().command_id.as_deref(), Some("tick-0"));
+}
+
+#[tokio::test]
+async fn codec_marker_is_stored() {
+    let journal = SqliteJournal::connect("sqlite::memory:").await.unwrap();
+    let id = "clock".to_entity_id();
+    let mut compressed = payload(&id, 0);
+    compressed.codec = Some("zstd".to_string());
+    journal.write(id.clone(), compressed).await.unwrap();
+    journal.write(id.clone(), payload(&id, 1)).await.unwrap();
+
+    assert_eq!(journal.read(id.clone(), 0).await.unwrap().codec.as_deref(), Some("zstd"));
+    assert_eq!(journal.read(id, 1).await.unwrap().codec, None);
+}
@@ -1,6 +1,7 @@
 #[cfg(feature = "process")]
 pub mod process;
 
+use std::any::{type_name, TypeId};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -87,34 +88,55 @@ pub trait ResolveMapping: 'static + Sync + Send + Sized {
 
 pub struct Mapper<T> {
     map: HashMap<ResolveType, Arc<dyn Resolver<T>>>,
+    /// Event type registered under each key, with its name.
+    events: HashMap<&'static str, (TypeId, &'static str)>,
 }
 
 impl<T> Mapper<T> 
 where
     T: 'static + Sync + Send,
 {
+    /// Resolve the payloads stored under any of [`Event::EVENT_TYPES`] with `H`.
+    ///
+    /// # Panics
+    ///
+    /// If another event type was registered under one of those keys, as their payloads could not be told apart.
     pub fn register<E: Event, H>(&mut self) -> &mut Self
     where
         H: ResolveHandler<E, T>,
     {
-        self.map.insert(
-            ResolveType::new(E::EVENT_TYPE, H::HANDLER_TYPE),
-            Arc::new(TypedResolver::<E, T, H>::default()),
-        );
+        self.claim::<E>();
+        for &key in E::EVENT_TYPES {
+            self.map.insert(
+                ResolveType::new(key, H::HANDLER_TYPE),
+                Arc::new(TypedResolver::<E, T, H>::default()),
+            );
+        }
         self
     }
     
+    /// Same as [`Mapper::register`], resolving with `resolver`.
     pub fn register_with<E: Event, R>(&mut self, resolver: R) -> &mut Self 
     where
         R: ResolverType<T>
     {
-        self.map.insert(
-            ResolveType::new(E::EVENT_TYPE, R::RESOLVE_TYPE),
-            Arc::new(resolver),
-        );
+        self.claim::<E>();
+        let resolver: Arc<dyn Resolver<T>> = Arc::new(resolver);
+        for &key in E::EVENT_TYPES {
+            self.map.insert(ResolveType::new(key, R::RESOLVE_TYPE), Arc::clone(&resolver));
+        }
         self
     }
 
+    fn claim<E: Event>(&mut self) {
+        for &key in E::EVENT_TYPES {
+            let (id, name) = *self.events.entry(key).or_insert((TypeId::of::<E>(), type_name::<E>()));
+            if id != TypeId::of::<E>() {
+                panic!("`{name}` and `{}` are both registered under the key `{key}`", type_name::<E>());
+            }
+        }
+    }
+
     pub fn find(&self, mut f: impl FnMut(&ResolveType) -> bool) -> Option<Arc<dyn Resolver<T>>> {
         self.map
             .iter()
@@ -128,6 +150,7 @@ where
             map: self.map.into_iter()
                 .filter(|(key, _)| f(key))
                 .collect(),
+            events: self.events,
         }
     }
 }
@@ -136,6 +159,7 @@ impl<T> Default for Mapper<T> {
     fn default() -> Self {
         Self {
             map: HashMap::default(),
+            events: HashMap::default(),
         }
     }
 }
@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_resolver::mapping::{Mapper, ResolveMapping};
+use nitinol_resolver::resolver::ResolveHandler;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub enum AccountEvent {
+    Opened,
+    Closed,
+}
+
+impl Event for AccountEvent {
+    const EVENT_TYPE: &'static str = "account";
+    const EVENT_TYPES: &'static [&'static str] = &["account.opened", "account.closed"];
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    fn event_type(&self) -> &'static str {
+        match self {
+            AccountEvent::Opened => "account.opened",
+            AccountEvent::Closed => "account.closed",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Closed;
+
+impl Event for Closed {
+    const EVENT_TYPE: &'static str = "account.closed";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[derive(Default)]
+pub struct Account {
+    events: Vec<AccountEvent>,
+}
+
+pub struct Record;
+
+#[async_trait]
+impl ResolveHandler<AccountEvent, Account> for Record {
+    const HANDLER_TYPE: &'static str = "record";
+    type Error = ();
+
+    async fn apply(entity: &mut Option<Account>, event: AccountEvent) -> Result<(), Self::Error> {
+        entity.get_or_insert_with(Account::default).events.push(event);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ResolveHandler<Closed, Account> for Record {
+    const HANDLER_TYPE: &'static str = "record";
+    type Error = ();
+
+    async fn apply(_: &mut Option<Account>, _: Closed) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ResolveMapping for Account {
+    fn mapping(mapper: &mut Mapper<Self>) {
+        mapper.register::<AccountEvent, Record>();
+    }
+}
+
+#[tokio::test]
+async fn variants_resolve_under_their_own_key() {
+    let mut mapper = Mapper::default();
+    Account::mapping(&mut mapper);
+
+    let mut account = None;
+    for event in [AccountEvent::Closed, AccountEvent::Opened] {
+        let resolver = mapper.find(|key| key.event() == event.event_type()).unwrap();
+        resolver.resolve(&mut account, &event.as_bytes().unwrap()).await.unwrap();
+    }
+    assert_eq!(account.unwrap().events, vec![AccountEvent::Closed, AccountEvent::Opened]);
+    assert!(mapper.find(|key| key.event() == AccountEvent::EVENT_TYPE).is_none());
+}
+
+#[test]
+#[should_panic(expected = "are both registered under the key `account.closed`")]
+fn types_sharing_a_key_are_rejected() {
+    let mut mapper = Mapper::<Account>::default();
+    mapper.register::<AccountEvent, Record>();
+    mapper.register::<Closed, Record>();
+}
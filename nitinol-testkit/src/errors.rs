@@ -0,0 +1,21 @@
+use nitinol_core::errors::SerializeError;
+use nitinol_projection::errors::ProjectionError;
+use nitinol_protocol::errors::ProtocolError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvariantError {
+    #[error("Replayed state diverged from the live state. live: {live}, replayed: {replayed}")]
+    Diverged { live: String, replayed: String },
+
+    #[error("Replay reached sequence {replayed}, but the live entity is at {live}")]
+    Sequence { live: i64, replayed: i64 },
+
+    #[error(transparent)]
+    Serialize(#[from] SerializeError),
+
+    #[error(transparent)]
+    Protocol(#[from] ProtocolError),
+
+    #[error(transparent)]
+    Projection(#[from] ProjectionError),
+}
@@ -0,0 +1,144 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use nitinol_core::command::Command;
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+use nitinol_projection::projector::EventProjector;
+use nitinol_protocol::io::Writer;
+use nitinol_protocol::Payload;
+use nitinol_resolver::mapping::ResolveMapping;
+
+use crate::errors::InvariantError;
+use crate::journal::InMemoryJournal;
+
+/// Checks that replaying the journal of an aggregate rebuilds the state it reached live.
+///
+/// Each command is handled and its event applied to a live entity built by `init`,
+/// then written to an [`InMemoryJournal`]. The journal is replayed onto a second entity
+/// with an [`EventProjector`], which must end up equal to the live one.
+///
+/// ```ignore
+/// Invariant::new(Account::default)
+///     .check([Deposit(10), Withdraw(4)]).await?;
+/// ```
+pub struct Invariant<T, F> {
+    init: F,
+    _entity: PhantomData<fn() -> T>,
+}
+
+/// Outcome of a successful [`Invariant::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub sequence: i64,
+}
+
+impl<T, F> Invariant<T, F>
+where
+    T: Process + ResolveMapping + PartialEq + Debug,
+    F: Fn() -> T,
+{
+    pub fn new(init: F) -> Self {
+        Self { init, _entity: PhantomData }
+    }
+
+    /// Run `commands` against a live entity, then compare it with its replay.
+    ///
+    /// Rejected commands are skipped, as they would be by a running process.
+    pub async fn check<C: Command>(&self, commands: impl IntoIterator<Item = C>) -> Result<Report, InvariantError>
+    where
+        T: CommandHandler<C>,
+        T: EventApplicator<<T as CommandHandler<C>>::Event>,
+    {
+        let mut live = (self.init)();
+        let id = live.aggregate_id();
+        let journal = InMemoryJournal::new();
+        let mut report = Report { accepted: 0, rejected: 0, sequence: 0 };
+
+        for command in commands {
+            let mut ctx = Context::new(report.sequence, Default::default()).with_id(id.clone());
+            let event = match live.handle(command, &mut ctx).await {
+                Ok(event) => event,
+                Err(_) => {
+                    report.rejected += 1;
+                    continue;
+                }
+            };
+            let payload = Payload::new(id.clone(), report.sequence, &event)?;
+            journal.write(id.clone(), payload).await?;
+            live.apply(event, &mut ctx).await;
+            report.accepted += 1;
+            report.sequence += 1;
+        }
+
+        let (replayed, sequence) = EventProjector::new(journal)
+            .projection_to_latest(id, ((self.init)(), 0))
+            .await?;
+
+        if replayed != live {
+            return Err(InvariantError::Diverged { live: format!("{live:?}"), replayed: format!("{replayed:?}") });
+        }
+        if sequence != report.sequence {
+            return Err(InvariantError::Sequence { live: report.sequence, replayed: sequence });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod property {
+    use std::fmt::Debug;
+
+    use nitinol_core::command::Command;
+    use nitinol_process::task::{CommandHandler, EventApplicator};
+    use nitinol_process::Process;
+    use nitinol_resolver::mapping::ResolveMapping;
+    use proptest::strategy::Strategy;
+    use proptest::test_runner::{Config, TestCaseError, TestRunner};
+
+    use super::Invariant;
+
+    impl<T, F> Invariant<T, F>
+    where
+        T: Process + ResolveMapping + PartialEq + Debug,
+        F: Fn() -> T,
+    {
+        /// [`Invariant::check`] sequences of commands generated by `commands`,
+        /// panicking with the smallest failing sequence proptest can shrink to.
+        ///
+        /// Must not be called from within an async runtime.
+        pub fn forall<C, S>(&self, commands: S)
+        where
+            C: Command + Debug,
+            S: Strategy<Value = Vec<C>>,
+            T: CommandHandler<C>,
+            T: EventApplicator<<T as CommandHandler<C>>::Event>,
+        {
+            self.forall_with(Config::default(), commands)
+        }
+
+        pub fn forall_with<C, S>(&self, config: Config, commands: S)
+        where
+            C: Command + Debug,
+            S: Strategy<Value = Vec<C>>,
+            T: CommandHandler<C>,
+            T: EventApplicator<<T as CommandHandler<C>>::Event>,
+        {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .expect("failed to build a runtime for the property test");
+
+            let result = TestRunner::new(config).run(&commands, |commands| {
+                runtime.block_on(self.check(commands))
+                    .map(|_| ())
+                    .map_err(|e| TestCaseError::fail(e.to_string()))
+            });
+
+            if let Err(e) = result {
+                panic!("{e}");
+            }
+        }
+    }
+}
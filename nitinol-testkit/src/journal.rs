@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+
+use async_trait::async_trait;
+use nitinol_core::errors::DeserializeError;
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_persistence::writer::staged_payload;
+use nitinol_process::batch::{Journal, Staged};
+use nitinol_protocol::errors::ProtocolError;
+use nitinol_protocol::inmemory::InMemoryEventStore;
+use nitinol_protocol::io::{Reader, Writer};
+use nitinol_protocol::Payload;
+
+/// Journal kept in memory that can be inspected from tests.
+///
+/// Usable as a [`Writer`], a [`Reader`] and a batch [`Journal`], with the semantics of
+/// [`InMemoryEventStore`]. Clones share the same storage.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryJournal {
+    store: InMemoryEventStore,
+}
+
+impl InMemoryJournal {
+    pub fn new() -> InMemoryJournal {
+        Self::default()
+    }
+
+    /// Payloads written for `id`, in sequence order.
+    pub fn payloads(&self, id: impl ToEntityId) -> Vec<Payload> {
+        self.store.payloads(&id.to_entity_id())
+    }
+
+    /// Events of type `E` written for `id`, in sequence order.
+    pub fn events<E: Event>(&self, id: impl ToEntityId) -> Result<Vec<E>, DeserializeError> {
+        self.payloads(id)
+            .iter()
+            .filter(|payload| E::EVENT_TYPES.contains(&payload.registry_key.as_str()))
+            .map(Payload::to_event)
+            .collect()
+    }
+
+    /// Number of payloads written for every entity.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.store.clear();
+    }
+
+    pub fn store(&self) -> &InMemoryEventStore {
+        &self.store
+    }
+}
+
+#[async_trait]
+impl Writer for InMemoryJournal {
+    async fn write(&self, id: EntityId, payload: Payload) -> Result<(), ProtocolError> {
+        self.store.write(id, payload).await
+    }
+
+    async fn write_batch(&self, id: EntityId, payloads: Vec<Payload>) -> Result<(), ProtocolError> {
+        self.store.write_batch(id, payloads).await
+    }
+}
+
+#[async_trait]
+impl Reader for InMemoryJournal {
+    async fn read(&self, id: EntityId, seq: i64) -> Result<Payload, ProtocolError> {
+        self.store.read(id, seq).await
+    }
+
+    async fn read_to(&self, id: EntityId, from: i64, to: i64) -> Result<BTreeSet<Payload>, ProtocolError> {
+        self.store.read_to(id, from, to).await
+    }
+}
+
+#[async_trait]
+impl Journal for InMemoryJournal {
+    async fn flush(&self, id: &EntityId, events: Vec<Staged>) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let payloads = events.into_iter()
+            .map(|staged| staged_payload(id, staged))
+            .collect();
+        Ok(self.store.write_batch(id.clone(), payloads).await?)
+    }
+}
@@ -0,0 +1,11 @@
+pub mod scenario;
+pub mod probe;
+pub mod journal;
+pub mod time;
+pub mod invariant;
+pub mod errors;
+
+pub use self::scenario::Scenario;
+pub use self::probe::TestProbe;
+pub use self::journal::InMemoryJournal;
+pub use self::invariant::Invariant;
@@ -0,0 +1,89 @@
+use std::any::{type_name, Any};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::errors::AlreadyExist;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_process::task::{EventApplicator, Receive};
+use nitinol_process::{Context, Process, Receptor};
+
+/// Process recording every message and event it receives, driven by a [`TestProbe`].
+pub struct Probe {
+    id: EntityId,
+    received: UnboundedSender<Box<dyn Any + Sync + Send>>,
+}
+
+impl Process for Probe {
+    fn aggregate_id(&self) -> EntityId {
+        self.id.clone()
+    }
+}
+
+#[async_trait]
+impl<M: Message> Receive<M> for Probe {
+    type Error = ();
+
+    async fn receive(&mut self, message: M, _: &mut Context) -> Result<(), Self::Error> {
+        let _ = self.received.send(Box::new(message));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<E: Event> EventApplicator<E> for Probe {
+    async fn apply(&mut self, event: E, _: &mut Context) {
+        let _ = self.received.send(Box::new(event));
+    }
+}
+
+/// Handle to a spawned [`Probe`], to hand its [`Receptor`] to the code under test
+/// and assert on what it received.
+pub struct TestProbe {
+    receptor: Receptor<Probe>,
+    received: Mutex<UnboundedReceiver<Box<dyn Any + Sync + Send>>>,
+    timeout: Duration,
+}
+
+impl TestProbe {
+    pub async fn spawn(system: &ProcessManager, id: impl ToEntityId) -> Result<TestProbe, AlreadyExist> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let receptor = system.spawn(Probe { id: id.to_entity_id(), received: tx }, 0).await?;
+        Ok(Self { receptor, received: Mutex::new(rx), timeout: Duration::from_secs(3) })
+    }
+
+    /// How long [`TestProbe::expect`] waits. Defaults to 3 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn receptor(&self) -> &Receptor<Probe> {
+        &self.receptor
+    }
+
+    /// Wait for the next message or event, panicking if it is not an `M` or nothing arrives in time.
+    pub async fn expect<M: Any>(&self) -> M {
+        let mut received = self.received.lock().await;
+        let next = tokio::time::timeout(self.timeout, received.recv())
+            .await
+            .unwrap_or_else(|_| panic!("timed out waiting for {}", type_name::<M>()))
+            .expect("probe stopped");
+        match (next as Box<dyn Any>).downcast::<M>() {
+            Ok(message) => *message,
+            Err(_) => panic!("expected {}, received another type", type_name::<M>()),
+        }
+    }
+
+    /// Panics if anything arrives within `within`.
+    pub async fn expect_nothing(&self, within: Duration) {
+        let mut received = self.received.lock().await;
+        if let Ok(Some(_)) = tokio::time::timeout(within, received.recv()).await {
+            panic!("expected nothing, received a message");
+        }
+    }
+}
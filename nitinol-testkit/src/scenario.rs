@@ -0,0 +1,129 @@
+use std::fmt::Debug;
+
+use nitinol_core::command::Command;
+use nitinol_core::event::Event;
+use nitinol_core::identifier::EntityId;
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+
+/// Given/when/then test of an aggregate.
+///
+/// Handlers are called directly on the entity, without a mailbox or a spawned task,
+/// so a scenario runs the same way on any runtime.
+///
+/// ```ignore
+/// Scenario::new(Account::default())
+///     .given(Deposited(10)).await
+///     .when(Withdraw(15)).await
+///     .then_rejection(&InsufficientFunds);
+/// ```
+pub struct Scenario<T: Process> {
+    entity: T,
+    id: EntityId,
+    sequence: i64,
+}
+
+impl<T: Process> Scenario<T> {
+    pub fn new(entity: T) -> Scenario<T> {
+        let id = entity.aggregate_id();
+        Self { entity, id, sequence: 0 }
+    }
+
+    /// Start the scenario at `sequence`, as if that many events had already been applied.
+    pub fn at(mut self, sequence: i64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Apply a past event to the entity.
+    pub async fn given<E: Event>(mut self, event: E) -> Self
+    where
+        T: EventApplicator<E>,
+    {
+        let mut ctx = self.context();
+        self.entity.apply(event, &mut ctx).await;
+        self.sequence += 1;
+        self
+    }
+
+    /// Handle `command` with the entity in its current state.
+    pub async fn when<C: Command>(self, command: C) -> Then<T, C>
+    where
+        T: CommandHandler<C>,
+    {
+        let mut ctx = self.context();
+        let result = self.entity.handle(command, &mut ctx).await;
+        Then { scenario: self, result }
+    }
+
+    pub fn entity(&self) -> &T {
+        &self.entity
+    }
+
+    pub fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    pub fn into_entity(self) -> T {
+        self.entity
+    }
+
+    fn context(&self) -> Context {
+        Context::new(self.sequence, Default::default()).with_id(self.id.clone())
+    }
+}
+
+/// Result of [`Scenario::when`], to assert on.
+pub struct Then<T: Process + CommandHandler<C>, C: Command> {
+    scenario: Scenario<T>,
+    result: Result<T::Event, T::Rejection>,
+}
+
+impl<T: Process + CommandHandler<C>, C: Command> Then<T, C> {
+    pub fn result(&self) -> &Result<T::Event, T::Rejection> {
+        &self.result
+    }
+
+    /// Panics unless the command produced `expected`.
+    #[track_caller]
+    pub fn then_event(self, expected: &T::Event) -> Self
+    where
+        T::Event: PartialEq + Debug,
+    {
+        match &self.result {
+            Ok(event) => assert_eq!(event, expected, "unexpected event"),
+            Err(rejection) => panic!("expected event {expected:?}, but the command was rejected with {rejection:?}"),
+        }
+        self
+    }
+
+    /// Panics unless the command was rejected with `expected`.
+    #[track_caller]
+    pub fn then_rejection(self, expected: &T::Rejection) -> Self
+    where
+        T::Event: Debug,
+        T::Rejection: PartialEq,
+    {
+        match &self.result {
+            Ok(event) => panic!("expected rejection {expected:?}, but the command produced {event:?}"),
+            Err(rejection) => assert_eq!(rejection, expected, "unexpected rejection"),
+        }
+        self
+    }
+
+    /// Apply the produced event and continue the scenario. Panics if the command was rejected.
+    pub async fn and_apply(self) -> Scenario<T>
+    where
+        T: EventApplicator<T::Event>,
+    {
+        match self.result {
+            Ok(event) => self.scenario.given(event).await,
+            Err(rejection) => panic!("cannot apply a rejected command: {rejection:?}"),
+        }
+    }
+
+    /// Discard the produced event and continue the scenario.
+    pub fn and_discard(self) -> Scenario<T> {
+        self.scenario
+    }
+}
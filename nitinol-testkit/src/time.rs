@@ -0,0 +1,14 @@
+//! Virtual time for process timeouts.
+//!
+//! Run the test on a paused clock with `#[tokio::test(start_paused = true)]`, or call [`pause`],
+//! then move the clock forward with [`elapse`] instead of sleeping.
+
+use std::time::Duration;
+
+pub use tokio::time::{advance, pause, resume};
+
+/// Move the paused clock forward by `duration` and let the tasks woken by it run.
+pub async fn elapse(duration: Duration) {
+    tokio::time::advance(duration).await;
+    tokio::task::yield_now().await;
+}
@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+use nitinol_projection::projection::Projection;
+use nitinol_projection::resolver::Project;
+use nitinol_resolver::mapping::{Mapper, ResolveMapping};
+use nitinol_testkit::errors::InvariantError;
+use nitinol_testkit::Invariant;
+
+#[derive(Debug, Clone)]
+pub enum AccountCommand {
+    Deposit(u32),
+    Withdraw(u32),
+}
+
+impl Command for AccountCommand {}
+
+#[derive(Debug)]
+pub enum AccountEvent {
+    Deposited(u32),
+    Withdrawn(u32),
+}
+
+impl Event for AccountEvent {
+    const EVENT_TYPE: &'static str = "account-event";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        let (tag, amount) = match self {
+            AccountEvent::Deposited(amount) => (0, amount),
+            AccountEvent::Withdrawn(amount) => (1, amount),
+        };
+        Ok([vec![tag], amount.to_be_bytes().to_vec()].concat())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let amount = u32::from_be_bytes(bytes[1..5].try_into().unwrap());
+        Ok(match bytes[0] {
+            0 => AccountEvent::Deposited(amount),
+            _ => AccountEvent::Withdrawn(amount),
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Account {
+    balance: u32,
+    /// Counts applications made by the live process only, so replay diverges when it is set.
+    live_only: Option<u32>,
+}
+
+impl Process for Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+}
+
+impl ResolveMapping for Account {
+    fn mapping(mapper: &mut Mapper<Self>) {
+        mapper.register::<AccountEvent, Project>();
+    }
+}
+
+#[async_trait]
+impl CommandHandler<AccountCommand> for Account {
+    type Event = AccountEvent;
+    type Rejection = ();
+
+    async fn handle(&self, command: AccountCommand, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        match command {
+            AccountCommand::Deposit(amount) => Ok(AccountEvent::Deposited(amount)),
+            AccountCommand::Withdraw(amount) if amount <= self.balance => Ok(AccountEvent::Withdrawn(amount)),
+            AccountCommand::Withdraw(_) => Err(()),
+        }
+    }
+}
+
+#[async_trait]
+impl EventApplicator<AccountEvent> for Account {
+    async fn apply(&mut self, event: AccountEvent, _: &mut Context) {
+        if let Some(applied) = &mut self.live_only {
+            *applied += 1;
+        }
+        Projection::apply(self, event).await.unwrap();
+    }
+}
+
+#[async_trait]
+impl Projection<AccountEvent> for Account {
+    type Rejection = ();
+
+    async fn apply(&mut self, event: AccountEvent) -> Result<(), Self::Rejection> {
+        match event {
+            AccountEvent::Deposited(amount) => self.balance += amount,
+            AccountEvent::Withdrawn(amount) => self.balance -= amount,
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn replay_matches_live_state() {
+    let commands = [
+        AccountCommand::Deposit(10),
+        AccountCommand::Withdraw(15),
+        AccountCommand::Withdraw(4),
+    ];
+    let report = Invariant::new(Account::default).check(commands).await.unwrap();
+
+    assert_eq!(report.accepted, 2);
+    assert_eq!(report.rejected, 1);
+    assert_eq!(report.sequence, 2);
+}
+
+#[tokio::test]
+async fn divergent_replay_is_reported() {
+    let init = || Account { live_only: Some(0), ..Account::default() };
+    let result = Invariant::new(init).check([AccountCommand::Deposit(1)]).await;
+
+    assert!(matches!(result, Err(InvariantError::Diverged { .. })));
+}
+
+#[cfg(feature = "proptest")]
+mod property {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use nitinol_testkit::Invariant;
+
+    use super::{Account, AccountCommand};
+
+    fn command() -> impl Strategy<Value = AccountCommand> {
+        prop_oneof![
+            (0..100u32).prop_map(AccountCommand::Deposit),
+            (0..100u32).prop_map(AccountCommand::Withdraw),
+        ]
+    }
+
+    #[test]
+    fn replay_matches_live_state_for_any_commands() {
+        Invariant::new(Account::default).forall(vec(command(), 0..32));
+    }
+
+    #[test]
+    #[should_panic(expected = "diverged")]
+    fn divergence_is_found() {
+        let init = || Account { live_only: Some(0), ..Account::default() };
+        Invariant::new(init).forall(vec(command(), 0..32));
+    }
+}
@@ -0,0 +1,71 @@
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::ToEntityId;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::task::EventApplicator;
+use nitinol_process::{Context, Process};
+use nitinol_protocol::io::{Reader, Writer};
+use nitinol_protocol::Payload;
+use nitinol_testkit::InMemoryJournal;
+use nitinol_core::identifier::EntityId;
+use async_trait::async_trait;
+
+#[derive(Debug, PartialEq)]
+pub struct Incremented(u8);
+
+impl Event for Incremented {
+    const EVENT_TYPE: &'static str = "incremented";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(vec![self.0])
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Incremented(bytes[0]))
+    }
+}
+
+pub struct Counter;
+
+impl Process for Counter {
+    fn aggregate_id(&self) -> EntityId {
+        "counter".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Incremented> for Counter {
+    async fn apply(&mut self, event: Incremented, ctx: &mut Context) {
+        ctx.stage(&event).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn written_payloads_can_be_read_back() {
+    let journal = InMemoryJournal::new();
+    let id = "counter".to_entity_id();
+    for seq in 0..4 {
+        let payload = Payload::new(id.clone(), seq, &Incremented(seq as u8)).unwrap();
+        journal.write(id.clone(), payload).await.unwrap();
+    }
+
+    assert_eq!(journal.len(), 4);
+    assert_eq!(journal.read(id.clone(), 2).await.unwrap().to_event::<Incremented>().unwrap(), Incremented(2));
+    assert!(journal.read(id.clone(), 9).await.is_err());
+    assert_eq!(journal.read_to(id.clone(), 1, 2).await.unwrap().len(), 2);
+
+    journal.clear();
+    assert!(journal.is_empty());
+}
+
+#[tokio::test]
+async fn batches_are_recorded() {
+    let journal = InMemoryJournal::new();
+    let system = ProcessManager::default().with_batch(8, journal.clone());
+    let refs = system.spawn(Counter, 0).await.unwrap();
+
+    refs.apply(Incremented(1)).await.unwrap();
+    refs.apply(Incremented(2)).await.unwrap();
+
+    assert_eq!(journal.events::<Incremented>("counter").unwrap(), vec![Incremented(1), Incremented(2)]);
+}
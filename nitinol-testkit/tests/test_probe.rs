@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::message::Message;
+use nitinol_testkit::TestProbe;
+
+#[derive(Debug, PartialEq)]
+pub struct Ping(u32);
+
+impl Message for Ping {}
+
+#[derive(Debug, PartialEq)]
+pub struct Ponged;
+
+impl Event for Ponged {
+    const EVENT_TYPE: &'static str = "ponged";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(Vec::new())
+    }
+
+    fn from_bytes(_: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Ponged)
+    }
+}
+
+#[tokio::test]
+async fn probe_records_messages_and_events_in_order() {
+    let system = ProcessManager::default();
+    let probe = TestProbe::spawn(&system, "probe").await.unwrap();
+
+    probe.receptor().send(Ping(1)).await.unwrap();
+    probe.receptor().apply(Ponged).await.unwrap();
+
+    assert_eq!(probe.expect::<Ping>().await, Ping(1));
+    assert_eq!(probe.expect::<Ponged>().await, Ponged);
+    probe.expect_nothing(Duration::from_millis(10)).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "timed out")]
+async fn expect_times_out() {
+    let system = ProcessManager::default();
+    let probe = TestProbe::spawn(&system, "probe").await.unwrap()
+        .with_timeout(Duration::from_millis(10));
+
+    probe.expect::<Ping>().await;
+}
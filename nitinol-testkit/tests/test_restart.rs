@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use nitinol_core::command::{Command, CommandId};
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::manager::ProcessManager;
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+use nitinol_projection::factory::Rehydrate;
+use nitinol_projection::projection::Projection;
+use nitinol_projection::projector::EventProjector;
+use nitinol_projection::resolver::Project;
+use nitinol_resolver::mapping::{Mapper, ResolveMapping};
+use nitinol_testkit::InMemoryJournal;
+
+pub struct Deposit {
+    request: &'static str,
+    amount: u32,
+}
+
+impl Command for Deposit {
+    fn command_id(&self) -> Option<CommandId> {
+        Some(CommandId::new(self.request))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Deposited(u32);
+
+impl Event for Deposited {
+    const EVENT_TYPE: &'static str = "deposited";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Deposited(u32::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+#[derive(Default)]
+pub struct Account {
+    balance: u32,
+}
+
+impl Process for Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+}
+
+impl ResolveMapping for Account {
+    fn mapping(mapper: &mut Mapper<Self>) {
+        mapper.register::<Deposited, Project>();
+    }
+}
+
+#[async_trait]
+impl Projection<Deposited> for Account {
+    type Rejection = ();
+
+    async fn apply(&mut self, event: Deposited) -> Result<(), Self::Rejection> {
+        self.balance += event.0;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Deposit> for Account {
+    type Event = Deposited;
+    type Rejection = ();
+
+    async fn handle(&self, command: Deposit, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Ok(Deposited(command.amount))
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Deposited> for Account {
+    async fn apply(&mut self, event: Deposited, ctx: &mut Context) {
+        ctx.stage(&event).unwrap();
+        self.balance += event.0;
+    }
+}
+
+pub struct Balance;
+
+impl Command for Balance {}
+
+#[async_trait]
+impl CommandHandler<Balance> for Account {
+    type Event = Deposited;
+    type Rejection = u32;
+
+    async fn handle(&self, _: Balance, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        Err(self.balance)
+    }
+}
+
+#[tokio::test]
+async fn redelivered_command_is_not_applied_after_restart() {
+    let journal = InMemoryJournal::new();
+    let system = ProcessManager::default()
+        .with_batch(1, journal.clone())
+        .with_factory(Rehydrate::new(EventProjector::new(journal.clone()), |_: &EntityId| Account::default()));
+
+    let refs = system.locate::<Account>("account").await.unwrap();
+    refs.entrust(Deposit { request: "req-1", amount: 10 }).await.unwrap();
+    assert_eq!(refs.handle(Balance).await.unwrap(), Err(10));
+    refs.stop().await.unwrap();
+    refs.watch().await;
+
+    let refs = system.locate::<Account>("account").await.unwrap();
+    assert_eq!(refs.handle(Balance).await.unwrap(), Err(10));
+    refs.entrust(Deposit { request: "req-1", amount: 10 }).await.unwrap();
+    refs.entrust(Deposit { request: "req-2", amount: 5 }).await.unwrap();
+
+    assert_eq!(refs.handle(Balance).await.unwrap(), Err(15));
+    assert_eq!(journal.events::<Deposited>("account").unwrap(), vec![Deposited(10), Deposited(5)]);
+}
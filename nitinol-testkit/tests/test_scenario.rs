@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use nitinol_core::command::Command;
+use nitinol_core::errors::{DeserializeError, SerializeError};
+use nitinol_core::event::Event;
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::task::{CommandHandler, EventApplicator};
+use nitinol_process::{Context, Process};
+use nitinol_testkit::Scenario;
+
+pub struct Withdraw(u32);
+
+impl Command for Withdraw {}
+
+#[derive(Debug, PartialEq)]
+pub struct Deposited(u32);
+
+#[derive(Debug, PartialEq)]
+pub struct Withdrawn(u32);
+
+impl Event for Deposited {
+    const EVENT_TYPE: &'static str = "deposited";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Deposited(u32::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+impl Event for Withdrawn {
+    const EVENT_TYPE: &'static str = "withdrawn";
+
+    fn as_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Withdrawn(u32::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InsufficientFunds;
+
+#[derive(Default)]
+pub struct Account {
+    balance: u32,
+}
+
+impl Process for Account {
+    fn aggregate_id(&self) -> EntityId {
+        "account".to_entity_id()
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Withdraw> for Account {
+    type Event = Withdrawn;
+    type Rejection = InsufficientFunds;
+
+    async fn handle(&self, command: Withdraw, _: &mut Context) -> Result<Self::Event, Self::Rejection> {
+        if command.0 > self.balance {
+            return Err(InsufficientFunds);
+        }
+        Ok(Withdrawn(command.0))
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Deposited> for Account {
+    async fn apply(&mut self, event: Deposited, _: &mut Context) {
+        self.balance += event.0;
+    }
+}
+
+#[async_trait]
+impl EventApplicator<Withdrawn> for Account {
+    async fn apply(&mut self, event: Withdrawn, _: &mut Context) {
+        self.balance -= event.0;
+    }
+}
+
+#[tokio::test]
+async fn command_produces_event() {
+    let scenario = Scenario::new(Account::default())
+        .given(Deposited(10)).await
+        .when(Withdraw(4)).await
+        .then_event(&Withdrawn(4))
+        .and_apply().await;
+
+    assert_eq!(scenario.entity().balance, 6);
+    assert_eq!(scenario.sequence(), 2);
+}
+
+#[tokio::test]
+async fn command_is_rejected() {
+    let scenario = Scenario::new(Account::default())
+        .given(Deposited(10)).await
+        .when(Withdraw(15)).await
+        .then_rejection(&InsufficientFunds)
+        .and_discard();
+
+    assert_eq!(scenario.into_entity().balance, 10);
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected event")]
+async fn wrong_event_panics() {
+    Scenario::new(Account::default())
+        .given(Deposited(10)).await
+        .when(Withdraw(4)).await
+        .then_event(&Withdrawn(5));
+}
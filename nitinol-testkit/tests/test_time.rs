@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use nitinol_core::identifier::{EntityId, ToEntityId};
+use nitinol_process::lifecycle::{self, Termination};
+use nitinol_process::Process;
+use nitinol_testkit::time::elapse;
+
+pub struct Idle;
+
+impl Process for Idle {
+    fn aggregate_id(&self) -> EntityId {
+        "idle".to_entity_id()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn process_times_out_on_virtual_time() {
+    let refs = lifecycle::run("idle", Idle, 0, Default::default(), Some(Duration::from_secs(60))).await.unwrap();
+
+    elapse(Duration::from_secs(59)).await;
+    assert_eq!(refs.termination(), None);
+
+    elapse(Duration::from_secs(2)).await;
+    assert_eq!(refs.watch().await, Termination::Timeout);
+}
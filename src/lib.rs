@@ -1,6 +1,8 @@
 pub use nitinol_core::identifier::*;
 pub use nitinol_core::event::Event;
-pub use nitinol_core::command::Command;
+pub use nitinol_core::command::{Command, CommandId};
+pub use nitinol_core::codec;
+pub use nitinol_core::default_codec;
 
 #[cfg(feature = "macro")]
 pub use self::macros::*;
@@ -9,6 +11,15 @@ pub use self::macros::*;
 mod macros {
     pub use nitinol_macro::Event;
     pub use nitinol_macro::Command;
+    pub use nitinol_macro::ResolveMapping;
+    pub use nitinol_macro::EntityId;
+    pub use nitinol_macro::{aggregate, handles, applies};
+}
+
+#[cfg(feature = "macro")]
+#[doc(hidden)]
+pub mod __private {
+    pub use async_trait::async_trait;
 }
 
 pub mod setup {
@@ -33,16 +44,40 @@ pub mod eventstream {
 pub mod protocol {
     pub use nitinol_protocol::Payload;
     pub use nitinol_protocol::io;
+    pub use nitinol_protocol::codec;
+    
+    #[cfg(feature = "protocol-inmemory")]
+    pub use nitinol_protocol::inmemory;
+    
+    #[cfg(any(feature = "protocol-sqlite", feature = "protocol-postgres"))]
+    pub use nitinol_protocol::sql;
+    
+    #[cfg(feature = "protocol-file")]
+    pub use nitinol_protocol::file;
+    
+    #[cfg(feature = "protocol-redb")]
+    pub use nitinol_protocol::kv;
+
+    #[cfg(feature = "protocol-encryption")]
+    pub use nitinol_protocol::crypto;
 }
 
 #[cfg(feature = "process")]
 pub mod process {
     pub use nitinol_process::any;
     pub use nitinol_process::manager;
+    pub use nitinol_process::supervisor;
+    pub use nitinol_process::metrics;
+    pub use nitinol_process::interceptor;
+    pub use nitinol_process::mailbox;
+    pub use nitinol_process::behavior;
+    pub use nitinol_process::batch;
+    pub use nitinol_process::factory;
+    pub use nitinol_process::router;
     pub use nitinol_process::Receptor;
     pub use nitinol_process::Context;
     pub use nitinol_process::Process;
-    pub use nitinol_process::task::{EventApplicator, CommandHandler};
+    pub use nitinol_process::task::{EventApplicator, CommandHandler, Query};
     
     #[cfg(feature = "persistence")]
     pub mod persistence {
@@ -67,6 +102,9 @@ pub mod projection {
     pub use nitinol_projection::projection::*;
     pub use nitinol_projection::projector;
     pub use nitinol_projection::resolver;
+    
+    #[cfg(feature = "process")]
+    pub use nitinol_projection::factory;
 }
 
 pub mod errors {